@@ -0,0 +1,522 @@
+//! Angle normalization, snapping and unit conversions, as used by the angle
+//! widgets (`AngleKnob`, `LinearCompass`, `PolarCompass`).
+//!
+//! Plain `f32` angles are in radians throughout, `Angle` wraps one for code
+//! that wants to be explicit about units.
+
+use std::f32::consts::{PI, TAU};
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use emath::normalized_angle;
+use strum::{Display, EnumIter};
+
+use crate::common::WrapMode;
+
+// ----------------------------------------------------------------------------
+
+/// Wraps `angle` to the `0.0..TAU` range.
+#[must_use]
+pub fn normalized_angle_unsigned_excl(angle: f32) -> f32 {
+    ((angle % TAU) + TAU) % TAU
+}
+
+/// Wraps `angle` to the `0.0..=TAU` range, keeping `TAU` itself (a full turn)
+/// as is.
+#[must_use]
+pub fn normalized_angle_unsigned_incl(angle: f32) -> f32 {
+    if angle < 0.0 {
+        ((angle % TAU) + TAU) % TAU
+    } else if angle > TAU {
+        angle % TAU
+    } else {
+        angle
+    }
+}
+
+/// Wraps `value` according to `wrap`, then clamps it to `min` and `max`.
+#[must_use]
+pub fn wrap_constrain_angle(
+    mut value: f32,
+    wrap: WrapMode,
+    min: Option<f32>,
+    max: Option<f32>,
+) -> f32 {
+    value = match wrap {
+        WrapMode::None => value,
+        WrapMode::Signed => normalized_angle(value),
+        WrapMode::Unsigned => normalized_angle_unsigned_excl(value),
+    };
+
+    if let Some(min) = min {
+        value = value.max(min);
+    }
+
+    if let Some(max) = max {
+        value = value.min(max);
+    }
+
+    value
+}
+
+/// Turns a pointer angle (`new_value`, in `-PI..=PI`) into a widget value.
+///
+/// `new_value` is snapped to multiples of `snap` and wrapped according to
+/// `wrap`. With `WrapMode::None` it's moved by whole turns to be the closest
+/// to `prev_value`, so that values keep counting turns. Finally it's clamped
+/// to `min` and `max`. Non-positive snaps are ignored.
+#[must_use]
+pub fn snap_wrap_constrain_angle(
+    prev_value: f32,
+    mut new_value: f32,
+    snap: Option<f32>,
+    wrap: WrapMode,
+    min: Option<f32>,
+    max: Option<f32>,
+) -> f32 {
+    if let Some(snap_angle) = snap.filter(|snap_angle| *snap_angle > 0.0) {
+        new_value = (new_value / snap_angle).round() * snap_angle;
+    }
+
+    if wrap == WrapMode::Unsigned {
+        new_value = normalized_angle_unsigned_excl(new_value);
+    }
+
+    if wrap == WrapMode::None {
+        let prev_turns = (prev_value / TAU).round();
+        new_value += prev_turns * TAU;
+
+        if new_value - prev_value > (TAU / 2.0) {
+            new_value -= TAU;
+        } else if new_value - prev_value < -(TAU / 2.0) {
+            new_value += TAU;
+        }
+    }
+
+    if let Some(min) = min {
+        new_value = new_value.max(min);
+    }
+
+    if let Some(max) = max {
+        new_value = new_value.min(max);
+    }
+
+    new_value
+}
+
+// ----------------------------------------------------------------------------
+
+/// Symmetric logarithm, linear within `-base..base` and logarithmic outside
+/// of it, so that it's defined for zero and negative values.
+pub trait SymLog {
+    #[must_use]
+    fn symlog(&self, base: Self) -> Self;
+}
+
+impl SymLog for f32 {
+    fn symlog(&self, base: Self) -> Self {
+        if self.abs() < base {
+            (self.abs() / base) * self.signum()
+        } else {
+            self.abs().log(base) * self.signum()
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Units angle widgets can take and report their values in.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum AngleUnit {
+    #[strum(to_string = "Radians")]
+    Radians,
+
+    #[strum(to_string = "Degrees")]
+    Degrees,
+
+    #[strum(to_string = "Gradians")]
+    Gradians,
+
+    #[strum(to_string = "Turns")]
+    Turns,
+
+    /// NATO mils, 6400 to a turn.
+    #[strum(to_string = "Mils")]
+    Mils,
+}
+
+impl AngleUnit {
+    #[must_use]
+    pub fn per_turn(&self) -> f32 {
+        match self {
+            AngleUnit::Radians => TAU,
+            AngleUnit::Degrees => 360.0,
+            AngleUnit::Gradians => 400.0,
+            AngleUnit::Turns => 1.0,
+            AngleUnit::Mils => 6400.0,
+        }
+    }
+
+    #[must_use]
+    pub fn to_radians(&self, value: f32) -> f32 {
+        match self {
+            AngleUnit::Radians => value,
+            _ => value * (TAU / self.per_turn()),
+        }
+    }
+
+    #[must_use]
+    pub fn from_radians(&self, radians: f32) -> f32 {
+        match self {
+            AngleUnit::Radians => radians,
+            _ => radians * (self.per_turn() / TAU),
+        }
+    }
+
+    #[must_use]
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            AngleUnit::Radians => "rad",
+            AngleUnit::Degrees => "°",
+            AngleUnit::Gradians => "gon",
+            AngleUnit::Turns => "tr",
+            AngleUnit::Mils => "mil",
+        }
+    }
+
+    /// Formats `value`, given in this unit, with the unit symbol.
+    #[must_use]
+    pub fn format(&self, value: f32) -> String {
+        match self {
+            AngleUnit::Radians => format!("{value:.3} rad"),
+            AngleUnit::Degrees => format!("{value:.1}°"),
+            AngleUnit::Gradians => format!("{value:.1} gon"),
+            AngleUnit::Turns => format!("{value:.3} tr"),
+            AngleUnit::Mils => format!("{value:.0} mil"),
+        }
+    }
+}
+
+/// Makes a widget working in radians take its value and `options` in `unit`,
+/// by converting at the `get_set_value` boundary.
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+pub(crate) fn widget_values_to_radians<'a>(
+    unit: AngleUnit,
+    get_set_value: &mut Box<dyn 'a + FnMut(Option<f32>) -> f32>,
+    options: &mut [&mut Option<f32>],
+) {
+    if unit == AngleUnit::Radians {
+        return;
+    }
+
+    let mut unit_get_set_value = std::mem::replace(get_set_value, Box::new(|_| 0.0));
+    *get_set_value = Box::new(move |radians: Option<f32>| {
+        unit.to_radians(unit_get_set_value(
+            radians.map(|radians| unit.from_radians(radians)),
+        ))
+    });
+
+    for option in options {
+        **option = option.map(|value| unit.to_radians(value));
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// An angle, stored in radians.
+///
+/// ```
+/// # use egui_extras_xt::angle::Angle;
+/// # use egui_extras_xt::common::WrapMode;
+/// let heading = Angle::from_degrees(350.0) + Angle::from_degrees(20.0);
+/// assert_eq!(heading.normalized(WrapMode::Unsigned).degrees().round(), 10.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Angle(f32);
+
+impl Angle {
+    pub const ZERO: Self = Self(0.0);
+    pub const HALF_TURN: Self = Self(PI);
+    pub const FULL_TURN: Self = Self(TAU);
+
+    #[must_use]
+    pub const fn from_radians(radians: f32) -> Self {
+        Self(radians)
+    }
+
+    #[must_use]
+    pub fn from_degrees(degrees: f32) -> Self {
+        Self::new(degrees, AngleUnit::Degrees)
+    }
+
+    #[must_use]
+    pub fn from_gradians(gradians: f32) -> Self {
+        Self::new(gradians, AngleUnit::Gradians)
+    }
+
+    #[must_use]
+    pub fn from_turns(turns: f32) -> Self {
+        Self::new(turns, AngleUnit::Turns)
+    }
+
+    #[must_use]
+    pub fn from_mils(mils: f32) -> Self {
+        Self::new(mils, AngleUnit::Mils)
+    }
+
+    /// An angle of `value` in `unit`.
+    #[must_use]
+    pub fn new(value: f32, unit: AngleUnit) -> Self {
+        Self(unit.to_radians(value))
+    }
+
+    #[must_use]
+    pub fn radians(self) -> f32 {
+        self.0
+    }
+
+    #[must_use]
+    pub fn degrees(self) -> f32 {
+        self.to_unit(AngleUnit::Degrees)
+    }
+
+    #[must_use]
+    pub fn gradians(self) -> f32 {
+        self.to_unit(AngleUnit::Gradians)
+    }
+
+    #[must_use]
+    pub fn turns(self) -> f32 {
+        self.to_unit(AngleUnit::Turns)
+    }
+
+    #[must_use]
+    pub fn mils(self) -> f32 {
+        self.to_unit(AngleUnit::Mils)
+    }
+
+    #[must_use]
+    pub fn to_unit(self, unit: AngleUnit) -> f32 {
+        unit.from_radians(self.0)
+    }
+
+    /// Wraps the angle to `-PI..=PI` for `WrapMode::Signed` and to
+    /// `0.0..TAU` for `WrapMode::Unsigned`, `WrapMode::None` keeps it as is.
+    #[must_use]
+    pub fn normalized(self, wrap: WrapMode) -> Self {
+        Self(wrap_constrain_angle(self.0, wrap, None, None))
+    }
+
+    /// The closest multiple of `step`, or the angle itself if `step` isn't
+    /// positive.
+    #[must_use]
+    pub fn snapped(self, step: Angle) -> Self {
+        if step.0 > 0.0 {
+            Self((self.0 / step.0).round() * step.0)
+        } else {
+            self
+        }
+    }
+
+    /// Signed rotation from `self` to `other` the short way around, within
+    /// `-PI..=PI`.
+    #[must_use]
+    pub fn shortest_delta(self, other: Angle) -> Self {
+        Self(normalized_angle(other.0 - self.0))
+    }
+
+    /// Interpolates from `self` towards `other` the short way around.
+    #[must_use]
+    pub fn lerp(self, other: Angle, t: f32) -> Self {
+        self + self.shortest_delta(other) * t
+    }
+}
+
+impl Display for Angle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&AngleUnit::Degrees.format(self.degrees()))
+    }
+}
+
+impl Add for Angle {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Angle {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Angle {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Angle {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Angle {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Mul<f32> for Angle {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Div<f32> for Angle {
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self {
+        Self(self.0 / rhs)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn same_direction(a: f32, b: f32) -> bool {
+        (a.sin() - b.sin()).abs() < EPSILON && (a.cos() - b.cos()).abs() < EPSILON
+    }
+
+    fn wrap_mode() -> impl Strategy<Value = WrapMode> {
+        prop_oneof![
+            Just(WrapMode::None),
+            Just(WrapMode::Signed),
+            Just(WrapMode::Unsigned),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn unsigned_excl_stays_below_a_full_turn(angle in -1e4f32..1e4) {
+            let normalized = normalized_angle_unsigned_excl(angle);
+
+            prop_assert!((0.0..TAU).contains(&normalized), "{normalized}");
+            prop_assert!(same_direction(normalized, angle));
+        }
+
+        #[test]
+        fn unsigned_incl_keeps_full_turns(angle in -1e4f32..1e4) {
+            let normalized = normalized_angle_unsigned_incl(angle);
+
+            prop_assert!((0.0..=TAU).contains(&normalized), "{normalized}");
+            prop_assert!(same_direction(normalized, angle));
+
+            if (0.0..=TAU).contains(&angle) {
+                prop_assert_eq!(normalized, angle);
+            }
+        }
+
+        #[test]
+        fn normalized_angles_are_in_range(angle in -1e4f32..1e4, wrap in wrap_mode()) {
+            let normalized = Angle::from_radians(angle).normalized(wrap).radians();
+
+            match wrap {
+                WrapMode::None => prop_assert_eq!(normalized, angle),
+                WrapMode::Signed => prop_assert!((-PI..=PI).contains(&normalized)),
+                WrapMode::Unsigned => prop_assert!((0.0..TAU).contains(&normalized)),
+            }
+
+            prop_assert!(same_direction(normalized, angle));
+        }
+
+        #[test]
+        fn constrained_angles_respect_limits(
+            prev_value in -1e3f32..1e3,
+            new_value in -PI..=PI,
+            snap in proptest::option::of(-1.0f32..1.0),
+            wrap in wrap_mode(),
+            min in -10.0f32..0.0,
+            max in 0.0f32..10.0,
+        ) {
+            let value =
+                snap_wrap_constrain_angle(prev_value, new_value, snap, wrap, Some(min), Some(max));
+            prop_assert!((min..=max).contains(&value), "{value}");
+
+            let value = wrap_constrain_angle(new_value, wrap, Some(min), Some(max));
+            prop_assert!((min..=max).contains(&value), "{value}");
+        }
+
+        #[test]
+        fn unwrapped_angles_follow_the_previous_value(
+            prev_value in -1e3f32..1e3,
+            new_value in -PI..=PI,
+        ) {
+            let value =
+                snap_wrap_constrain_angle(prev_value, new_value, None, WrapMode::None, None, None);
+
+            prop_assert!((value - prev_value).abs() <= PI + EPSILON, "{value}");
+            prop_assert!(same_direction(value, new_value));
+        }
+
+        #[test]
+        fn snapped_angles_are_the_closest_multiple(angle in -1e3f32..1e3, step in -1.0f32..1.0) {
+            let snapped = Angle::from_radians(angle).snapped(Angle::from_radians(step)).radians();
+
+            if step > 0.0 {
+                prop_assert!((snapped - angle).abs() <= step / 2.0 + EPSILON);
+                prop_assert!((snapped - (snapped / step).round() * step).abs() < EPSILON);
+            } else {
+                prop_assert_eq!(snapped, angle);
+            }
+        }
+
+        #[test]
+        fn shortest_delta_takes_the_short_way(a in -1e3f32..1e3, b in -1e3f32..1e3) {
+            let (a, b) = (Angle::from_radians(a), Angle::from_radians(b));
+            let delta = a.shortest_delta(b).radians();
+
+            prop_assert!((-PI..=PI).contains(&delta), "{delta}");
+            prop_assert!(same_direction((a + a.shortest_delta(b)).radians(), b.radians()));
+            prop_assert!(same_direction(a.lerp(b, 1.0).radians(), b.radians()));
+            prop_assert_eq!(a.lerp(b, 0.0), a);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        AngleUnit::iter().for_each(assert_serde_round_trip);
+
+        assert_serde_round_trip(Angle::from_degrees(-135.0));
+        assert_serde_round_trip(Angle::FULL_TURN);
+    }
+}
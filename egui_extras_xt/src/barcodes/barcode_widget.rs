@@ -5,7 +5,7 @@ use barcoders::error::Error;
 use egui::util::cache::{ComputerMut, FrameCache};
 use egui::{
     vec2, Align2, Color32, FontFamily, FontId, Rect, Response, Sense, Stroke, StrokeKind, Ui,
-    Widget,
+    Widget, WidgetInfo, WidgetType,
 };
 use emath::GuiRounding;
 
@@ -19,12 +19,14 @@ use barcoders::sym::ean8::EAN8;
 use barcoders::sym::ean_supp::EANSUPP;
 use barcoders::sym::tf::TF;
 
+use crate::theme::XtTheme;
 use strum::{Display, EnumIter};
 
 // ----------------------------------------------------------------------------
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, EnumIter, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum BarcodeKind {
     #[strum(to_string = "Codabar")]
     Codabar,
@@ -108,8 +110,8 @@ pub struct BarcodeWidget<'a> {
     label: Option<&'a str>,
     label_height: f32,
     label_top_margin: f32,
-    foreground_color: Color32,
-    background_color: Color32,
+    foreground_color: Option<Color32>,
+    background_color: Option<Color32>,
 }
 
 impl<'a> BarcodeWidget<'a> {
@@ -124,8 +126,8 @@ impl<'a> BarcodeWidget<'a> {
             label: None,
             label_height: 20.0,
             label_top_margin: 4.0,
-            foreground_color: Color32::BLACK,
-            background_color: Color32::WHITE,
+            foreground_color: None,
+            background_color: None,
         }
     }
 
@@ -170,18 +172,26 @@ impl<'a> BarcodeWidget<'a> {
     }
 
     pub fn foreground_color(mut self, foreground_color: impl Into<Color32>) -> Self {
-        self.foreground_color = foreground_color.into();
+        self.foreground_color = Some(foreground_color.into());
         self
     }
 
     pub fn background_color(mut self, background_color: impl Into<Color32>) -> Self {
-        self.background_color = background_color.into();
+        self.background_color = Some(background_color.into());
         self
     }
 }
 
 impl<'a> Widget for BarcodeWidget<'a> {
     fn ui(self, ui: &mut Ui) -> Response {
+        let theme = XtTheme::current(ui);
+        let foreground_color = self
+            .foreground_color
+            .unwrap_or(theme.barcode_foreground_color);
+        let background_color = self
+            .background_color
+            .unwrap_or(theme.barcode_background_color);
+
         let cached_barcode = ui.memory_mut(|memory| {
             let cache = memory.caches.cache::<BarcodeCache<'_>>();
             cache.get((self.barcode_kind, self.value))
@@ -202,12 +212,15 @@ impl<'a> Widget for BarcodeWidget<'a> {
             };
 
             let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+            response.widget_info(|| {
+                WidgetInfo::labeled(WidgetType::Image, ui.is_enabled(), self.value)
+            });
 
             if ui.is_rect_visible(rect) {
                 ui.painter().rect(
                     rect,
                     ui.style().visuals.noninteractive().corner_radius,
-                    self.background_color,
+                    background_color,
                     Stroke::NONE,
                     StrokeKind::Middle,
                 );
@@ -226,8 +239,7 @@ impl<'a> Widget for BarcodeWidget<'a> {
                         )
                     })
                     .for_each(|bar_rect| {
-                        ui.painter()
-                            .rect_filled(bar_rect, 0.0, self.foreground_color);
+                        ui.painter().rect_filled(bar_rect, 0.0, foreground_color);
                     });
 
                 if let Some(label) = self.label {
@@ -236,7 +248,7 @@ impl<'a> Widget for BarcodeWidget<'a> {
                         Align2::CENTER_BOTTOM,
                         label,
                         FontId::new(self.label_height, FontFamily::Proportional),
-                        self.foreground_color,
+                        foreground_color,
                     );
                 }
             }
@@ -250,3 +262,36 @@ impl<'a> Widget for BarcodeWidget<'a> {
         }
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        BarcodeKind::iter().for_each(assert_serde_round_trip);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_an_image_of_the_encoded_text() {
+        let node = accesskit_node(|ui| ui.add(BarcodeWidget::new("HELLO")));
+
+        assert_eq!(node.role(), Role::Image);
+        assert_eq!(node.label(), Some("HELLO"));
+    }
+}
@@ -2,9 +2,13 @@ use std::borrow::Borrow;
 use std::sync::Arc;
 
 use egui::util::cache::{ComputerMut, FrameCache};
-use egui::{vec2, Color32, Rect, Response, Sense, Stroke, StrokeKind, Ui, Vec2, Widget};
+use egui::{
+    vec2, Color32, Rect, Response, Sense, Stroke, StrokeKind, Ui, Vec2, Widget, WidgetInfo,
+    WidgetType,
+};
 use emath::GuiRounding;
 
+use crate::theme::XtTheme;
 use qrcode::{Color, QrCode, QrResult};
 
 // ----------------------------------------------------------------------------
@@ -30,8 +34,8 @@ pub struct QrCodeWidget<'a> {
     value: &'a str,
     module_size: usize,
     quiet_zone: usize,
-    foreground_color: Color32,
-    background_color: Color32,
+    foreground_color: Option<Color32>,
+    background_color: Option<Color32>,
 }
 
 impl<'a> QrCodeWidget<'a> {
@@ -40,8 +44,8 @@ impl<'a> QrCodeWidget<'a> {
             value,
             module_size: 6,
             quiet_zone: 4,
-            foreground_color: Color32::BLACK,
-            background_color: Color32::WHITE,
+            foreground_color: None,
+            background_color: None,
         }
     }
 
@@ -56,18 +60,26 @@ impl<'a> QrCodeWidget<'a> {
     }
 
     pub fn foreground_color(mut self, foreground_color: impl Into<Color32>) -> Self {
-        self.foreground_color = foreground_color.into();
+        self.foreground_color = Some(foreground_color.into());
         self
     }
 
     pub fn background_color(mut self, background_color: impl Into<Color32>) -> Self {
-        self.background_color = background_color.into();
+        self.background_color = Some(background_color.into());
         self
     }
 }
 
 impl<'a> Widget for QrCodeWidget<'a> {
     fn ui(self, ui: &mut Ui) -> Response {
+        let theme = XtTheme::current(ui);
+        let foreground_color = self
+            .foreground_color
+            .unwrap_or(theme.barcode_foreground_color);
+        let background_color = self
+            .background_color
+            .unwrap_or(theme.barcode_background_color);
+
         let cached_qr_code = ui.memory_mut(|memory| {
             let cache = memory.caches.cache::<QrCodeCache<'_>>();
             cache.get(self.value)
@@ -80,12 +92,15 @@ impl<'a> Widget for QrCodeWidget<'a> {
                 Vec2::splat((qr_code.width() + self.quiet_zone * 2) as f32 * module_size);
 
             let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+            response.widget_info(|| {
+                WidgetInfo::labeled(WidgetType::Image, ui.is_enabled(), self.value)
+            });
 
             if ui.is_rect_visible(rect) {
                 ui.painter().rect(
                     rect,
                     ui.style().visuals.noninteractive().corner_radius,
-                    self.background_color,
+                    background_color,
                     Stroke::NONE,
                     StrokeKind::Middle,
                 );
@@ -110,8 +125,7 @@ impl<'a> Widget for QrCodeWidget<'a> {
                         )
                     })
                     .for_each(|module_rect| {
-                        ui.painter()
-                            .rect_filled(module_rect, 0.0, self.foreground_color);
+                        ui.painter().rect_filled(module_rect, 0.0, foreground_color);
                     });
             }
 
@@ -124,3 +138,21 @@ impl<'a> Widget for QrCodeWidget<'a> {
         }
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_an_image_of_the_encoded_text() {
+        let node = accesskit_node(|ui| ui.add(QrCodeWidget::new("HELLO")));
+
+        assert_eq!(node.role(), Role::Image);
+        assert_eq!(node.label(), Some("HELLO"));
+    }
+}
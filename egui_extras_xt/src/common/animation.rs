@@ -0,0 +1,307 @@
+use std::f32::consts::PI;
+
+use egui::Ui;
+use strum::{Display, EnumIter};
+
+use crate::common::{validate_not_negative, validate_positive, ValidationError};
+
+// ----------------------------------------------------------------------------
+
+/// Easing curves for `Animation::Eased`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Easing {
+    #[strum(to_string = "Linear")]
+    Linear,
+
+    #[strum(to_string = "Quadratic in")]
+    QuadraticIn,
+
+    #[strum(to_string = "Quadratic out")]
+    QuadraticOut,
+
+    #[strum(to_string = "Quadratic in/out")]
+    QuadraticInOut,
+
+    #[strum(to_string = "Cubic in")]
+    CubicIn,
+
+    #[strum(to_string = "Cubic out")]
+    CubicOut,
+
+    #[strum(to_string = "Cubic in/out")]
+    CubicInOut,
+
+    #[strum(to_string = "Sine in/out")]
+    SineInOut,
+}
+
+impl Easing {
+    /// Maps animation progress `t` in `0.0..=1.0` to eased progress.
+    #[must_use]
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadraticIn => t * t,
+            Easing::QuadraticOut => 1.0 - (1.0 - t).powi(2),
+            Easing::QuadraticInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t.powi(3),
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t.powi(3)
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// How an `animated` widget moves its displayed value towards the actual one.
+///
+/// Animations are kept per widget, a new target continues from wherever the
+/// displayed value is at the moment.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Animation {
+    /// Reaches the target in `duration` seconds, following `easing`.
+    #[strum(to_string = "Eased")]
+    Eased { duration: f32, easing: Easing },
+
+    /// Follows the target like a mass on a spring oscillating at `frequency`
+    /// hertz. A `damping_ratio` of 1.0 is critically damped (the fastest
+    /// approach without overshooting), lower values overshoot and bounce.
+    #[strum(to_string = "Spring")]
+    Spring { frequency: f32, damping_ratio: f32 },
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::Eased {
+            duration: 0.1,
+            easing: Easing::Linear,
+        }
+    }
+}
+
+impl Animation {
+    #[must_use]
+    pub fn eased(duration: f32, easing: Easing) -> Self {
+        Self::Eased { duration, easing }
+    }
+
+    /// Critically damped spring.
+    #[must_use]
+    pub fn spring(frequency: f32) -> Self {
+        Self::Spring {
+            frequency,
+            damping_ratio: 1.0,
+        }
+    }
+
+    /// Linear easing over the `animation_time` of the style of `ui`, used by
+    /// widgets without an animation of their own.
+    #[must_use]
+    pub fn from_style(ui: &Ui) -> Self {
+        Self::eased(ui.style().animation_time, Easing::Linear)
+    }
+
+    /// Durations can't be negative, springs need a positive frequency and a
+    /// damping ratio that isn't negative.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match *self {
+            Animation::Eased { duration, .. } => {
+                validate_not_negative("animation duration", duration)
+            }
+            Animation::Spring {
+                frequency,
+                damping_ratio,
+            } => {
+                validate_positive("spring frequency", frequency)?;
+                validate_not_negative("spring damping ratio", damping_ratio)
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+#[derive(Clone, Copy, Debug)]
+struct AnimationState {
+    from: f32,
+    to: f32,
+    start_time: f64,
+    value: f32,
+    velocity: f32,
+    last_time: f64,
+}
+
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+impl AnimationState {
+    fn at_rest(value: f32, time: f64) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start_time: time,
+            value,
+            velocity: 0.0,
+            last_time: time,
+        }
+    }
+}
+
+/// Animates the displayed value of the widget `id` towards `target`, and
+/// returns the value to display this frame. Without an `animation`, follows
+/// the animation time of the style.
+///
+/// Unlike `Context::animate_value_with_time()`, the state lives in the temp
+/// memory of the widget, so it can be reset with `reset_animation()` without
+/// touching the animations of anything else.
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) fn animate_value(
+    ui: &Ui,
+    id: egui::Id,
+    target: f32,
+    animation: Option<Animation>,
+) -> f32 {
+    let animation = animation.unwrap_or_else(|| Animation::from_style(ui));
+    let state_id = id.with("animation");
+    let (time, dt) = ui.input(|input| (input.time, input.stable_dt));
+
+    let Some(mut state) = ui.data(|data| data.get_temp::<AnimationState>(state_id)) else {
+        reset_animation(ui, id, target);
+        return target;
+    };
+
+    if state.to != target {
+        state.from = state.value;
+        state.to = target;
+        state.start_time = time;
+    }
+
+    let done = match animation {
+        Animation::Eased { duration, easing } => {
+            let t = if duration > 0.0 {
+                (time - state.start_time) as f32 / duration
+            } else {
+                1.0
+            };
+            state.value = emath::lerp(state.from..=state.to, easing.apply(t));
+            t >= 1.0
+        }
+        Animation::Spring {
+            frequency,
+            damping_ratio,
+        } => {
+            // Semi-implicit Euler in small steps stays stable for stiff
+            // springs and long frames alike.
+            let omega = 2.0 * PI * frequency;
+            let mut remaining = ((time - state.last_time) as f32).clamp(0.0, dt.max(1.0 / 60.0));
+
+            while remaining > 0.0 {
+                let step = remaining.min(1.0 / 240.0);
+                let acceleration = omega * omega * (state.to - state.value)
+                    - 2.0 * damping_ratio * omega * state.velocity;
+                state.velocity += acceleration * step;
+                state.value += state.velocity * step;
+                remaining -= step;
+            }
+
+            let scale = (state.to - state.from).abs().max(f32::EPSILON);
+            let settled = (state.to - state.value).abs() < scale * 1e-3
+                && state.velocity.abs() < scale * 1e-2;
+
+            if settled || omega == 0.0 {
+                state.velocity = 0.0;
+            }
+
+            settled || omega == 0.0
+        }
+    };
+
+    if done {
+        state.value = state.to;
+    } else {
+        ui.ctx().request_repaint();
+    }
+
+    state.last_time = time;
+    ui.data_mut(|data| data.insert_temp(state_id, state));
+
+    state.value
+}
+
+/// Jumps the displayed value of the widget `id` to `value`, e.g. after the
+/// widget displayed the actual value directly while being dragged.
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) fn reset_animation(ui: &Ui, id: egui::Id, value: f32) {
+    let time = ui.input(|input| input.time);
+    ui.data_mut(|data| {
+        data.insert_temp(id.with("animation"), AnimationState::at_rest(value, time));
+    });
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod validation_tests {
+    use super::*;
+
+    #[test]
+    fn rejects_bad_parameters() {
+        assert!(Animation::eased(0.0, Easing::Linear).validate().is_ok());
+        assert!(Animation::spring(4.0).validate().is_ok());
+
+        for animation in [
+            Animation::eased(-1.0, Easing::Linear),
+            Animation::eased(f32::NAN, Easing::Linear),
+            Animation::spring(0.0),
+            Animation::spring(f32::NAN),
+            Animation::Spring {
+                frequency: 4.0,
+                damping_ratio: -0.5,
+            },
+        ] {
+            assert!(animation.validate().is_err(), "{animation:?}");
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        Easing::iter().for_each(assert_serde_round_trip);
+
+        for easing in Easing::iter() {
+            assert_serde_round_trip(Animation::eased(0.25, easing));
+        }
+
+        assert_serde_round_trip(Animation::Spring {
+            frequency: 4.0,
+            damping_ratio: 0.5,
+        });
+    }
+}
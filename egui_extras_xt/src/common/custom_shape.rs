@@ -0,0 +1,192 @@
+use std::f32::consts::TAU;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use emath::lerp;
+use strum::{Display, EnumIter};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum RadiusInterpolation {
+    #[strum(to_string = "Linear")]
+    Linear,
+
+    #[strum(to_string = "Cubic")]
+    Cubic,
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone)]
+enum CustomShapeKind {
+    Function(Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+    RadiusTable(Arc<[f32]>, RadiusInterpolation),
+}
+
+/// User-defined outline for `WidgetShape::Custom`, either a polar radius
+/// function or a periodic table of radii evenly spaced around the full turn.
+///
+/// Radii are relative to the widget radius, `1.0` touches the edge of the
+/// widget, like the built-in shapes do.
+#[derive(Clone)]
+pub struct CustomShape {
+    kind: CustomShapeKind,
+}
+
+impl CustomShape {
+    /// The function receives angles normalized to `(0..TAU)`.
+    pub fn from_fn(radius_fn: impl Fn(f32) -> f32 + Send + Sync + 'static) -> Self {
+        Self {
+            kind: CustomShapeKind::Function(Arc::new(radius_fn)),
+        }
+    }
+
+    /// Sample `i` of `radii` lies at the angle `i / radii.len() * TAU`, the
+    /// last sample wraps around to the first one.
+    pub fn from_radius_table(
+        radii: impl Into<Arc<[f32]>>,
+        interpolation: RadiusInterpolation,
+    ) -> Self {
+        Self {
+            kind: CustomShapeKind::RadiusTable(radii.into(), interpolation),
+        }
+    }
+
+    #[must_use]
+    pub fn radius_table(&self) -> Option<(&[f32], RadiusInterpolation)> {
+        match &self.kind {
+            CustomShapeKind::Function(_) => None,
+            CustomShapeKind::RadiusTable(radii, interpolation) => Some((radii, *interpolation)),
+        }
+    }
+
+    pub(crate) fn eval(&self, theta: f32) -> f32 {
+        let theta = theta.rem_euclid(TAU);
+
+        match &self.kind {
+            CustomShapeKind::Function(radius_fn) => radius_fn(theta),
+            CustomShapeKind::RadiusTable(radii, interpolation) => {
+                if radii.is_empty() {
+                    return 1.0;
+                }
+
+                let position = theta / TAU * radii.len() as f32;
+                let index = position.floor() as usize;
+                let t = position.fract();
+
+                let sample = |offset: isize| {
+                    radii[(index as isize + offset).rem_euclid(radii.len() as isize) as usize]
+                };
+
+                match interpolation {
+                    RadiusInterpolation::Linear => lerp(sample(0)..=sample(1), t),
+                    RadiusInterpolation::Cubic => {
+                        // Catmull-Rom spline through the neighbouring samples
+                        let (p0, p1, p2, p3) = (sample(-1), sample(0), sample(1), sample(2));
+                        0.5 * ((2.0 * p1)
+                            + (p2 - p0) * t
+                            + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+                            + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t * t * t)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Linearly interpolated tables have a kink at every sample.
+    #[cfg(feature = "knobs")]
+    pub(crate) fn corner_angles(&self) -> Vec<f32> {
+        match &self.kind {
+            CustomShapeKind::RadiusTable(radii, RadiusInterpolation::Linear) => (0..radii.len())
+                .map(|index| index as f32 / radii.len() as f32 * TAU)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl PartialEq for CustomShape {
+    /// Functions are compared by identity, tables by value.
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.kind, &other.kind) {
+            (CustomShapeKind::Function(a), CustomShapeKind::Function(b)) => Arc::ptr_eq(a, b),
+            (
+                CustomShapeKind::RadiusTable(radii_a, interpolation_a),
+                CustomShapeKind::RadiusTable(radii_b, interpolation_b),
+            ) => radii_a == radii_b && interpolation_a == interpolation_b,
+            _ => false,
+        }
+    }
+}
+
+/// Serialized form of radius table shapes.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct RadiusTable<'a> {
+    radii: std::borrow::Cow<'a, [f32]>,
+    interpolation: RadiusInterpolation,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CustomShape {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.radius_table() {
+            Some((radii, interpolation)) => RadiusTable {
+                radii: radii.into(),
+                interpolation,
+            }
+            .serialize(serializer),
+            None => Err(serde::ser::Error::custom(
+                "custom shapes built from a radius function can't be serialized",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CustomShape {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RadiusTable {
+            radii,
+            interpolation,
+        } = RadiusTable::deserialize(deserializer)?;
+        Ok(Self::from_radius_table(radii.into_owned(), interpolation))
+    }
+}
+
+impl Debug for CustomShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            CustomShapeKind::Function(_) => f.write_str("CustomShape::Function(..)"),
+            CustomShapeKind::RadiusTable(radii, interpolation) => f
+                .debug_tuple("CustomShape::RadiusTable")
+                .field(radii)
+                .field(interpolation)
+                .finish(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        RadiusInterpolation::iter().for_each(assert_serde_round_trip);
+
+        for interpolation in RadiusInterpolation::iter() {
+            assert_serde_round_trip(CustomShape::from_radius_table(
+                [1.0, 0.5, 0.75, 0.5],
+                interpolation,
+            ));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use egui::text::{CCursor, CCursorRange};
+use egui::{Align, Align2, Area, Key, Order, Response, Stroke, StrokeKind, TextEdit, Ui};
+use strum::IntoEnumIterator;
+
+use crate::angle::AngleUnit;
+
+// ----------------------------------------------------------------------------
+
+/// Text field overlay for typing in an exact value, opened by Ctrl+click (Cmd
+/// on macOS) or by Enter while the widget has focus.
+///
+/// `format` turns the current value into the initial text, `parse` turns the
+/// typed text back into a value, or `None` when it's invalid or out of range.
+/// Returns the parsed value once Enter commits it. Escape or clicking outside
+/// the field cancels the edit, invalid text keeps it open.
+pub(crate) fn inline_editor(
+    ui: &Ui,
+    response: &Response,
+    value: f32,
+    format: impl Fn(f32) -> String,
+    parse: impl Fn(&str) -> Option<f32>,
+) -> Option<f32> {
+    let id = response.id.with("inline_editor");
+
+    let open_requested = (response.clicked() && ui.input(|input| input.modifiers.command_only()))
+        || (response.has_focus() && ui.input(|input| input.key_pressed(Key::Enter)));
+
+    let (mut text, just_opened) = match ui.data(|data| data.get_temp::<String>(id)) {
+        Some(text) => (text, false),
+        None if open_requested => (format(value), true),
+        None => return None,
+    };
+
+    let valid = parse(&text).is_some();
+    let mut committed = None;
+    let mut closed = false;
+
+    Area::new(id)
+        .order(Order::Foreground)
+        .fixed_pos(response.rect.center())
+        .pivot(Align2::CENTER_CENTER)
+        .show(ui.ctx(), |ui| {
+            let mut output = TextEdit::singleline(&mut text)
+                .desired_width(response.rect.width().max(64.0))
+                .horizontal_align(Align::Center)
+                .show(ui);
+
+            if !valid {
+                ui.painter().rect_stroke(
+                    output.response.rect,
+                    ui.visuals().widgets.inactive.corner_radius,
+                    Stroke::new(1.0, ui.visuals().error_fg_color),
+                    StrokeKind::Outside,
+                );
+            }
+
+            if just_opened {
+                output.response.request_focus();
+                output.state.cursor.set_char_range(Some(CCursorRange::two(
+                    CCursor::new(0),
+                    CCursor::new(output.galley.text().chars().count()),
+                )));
+                output.state.store(ui.ctx(), output.response.id);
+            } else if output.response.lost_focus() {
+                if ui.input(|input| input.key_pressed(Key::Enter)) {
+                    match parse(&text) {
+                        Some(value) => {
+                            committed = Some(value);
+                            closed = true;
+                        }
+                        None => output.response.request_focus(),
+                    }
+                } else {
+                    closed = true;
+                }
+            }
+        });
+
+    if closed {
+        ui.data_mut(|data| data.remove::<String>(id));
+        response.request_focus();
+    } else {
+        ui.data_mut(|data| data.insert_temp(id, text));
+    }
+
+    committed
+}
+
+/// Parses a number, ignoring surrounding whitespace and an optional `unit`
+/// suffix.
+pub(crate) fn parse_with_unit(text: &str, unit: &str) -> Option<f32> {
+    text.trim()
+        .trim_end_matches(unit)
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+/// `inline_editor()` for angle widgets, edited in `unit` and limited to `min`
+/// and `max`. Typed values may carry the symbol of any other `AngleUnit`,
+/// e.g. `45°` on a knob working in radians.
+pub(crate) fn angle_inline_editor(
+    ui: &Ui,
+    response: &Response,
+    value: f32,
+    unit: AngleUnit,
+    min: Option<f32>,
+    max: Option<f32>,
+) -> Option<f32> {
+    inline_editor(
+        ui,
+        response,
+        value,
+        |value| unit.format(unit.from_radians(value)),
+        |text| {
+            let text_unit = AngleUnit::iter()
+                .find(|unit| text.trim_end().ends_with(unit.symbol()))
+                .unwrap_or(unit);
+
+            parse_with_unit(text, text_unit.symbol())
+                .map(|value| text_unit.to_radians(value))
+                .filter(|value| min.is_none_or(|min| *value >= min))
+                .filter(|value| max.is_none_or(|max| *value <= max))
+        },
+    )
+}
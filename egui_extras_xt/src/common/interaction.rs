@@ -0,0 +1,370 @@
+use std::f32::consts::{PI, TAU};
+
+use egui::{Event, EventFilter, Id, Key, MouseWheelUnit, Response, Sense, Ui, WidgetInfo};
+use emath::{Pos2, Vec2};
+
+use crate::angle::AngleUnit;
+use crate::common::{accesskit_numeric_range, WrapMode};
+
+// ----------------------------------------------------------------------------
+
+/// Adds `sense` to a hover-only (or focusable-only) `response` while the
+/// pointer is inside the painted shape of the widget, so the transparent
+/// corners of its bounding rect don't react to the pointer. Widgets keep
+/// sensing while being dragged.
+pub(crate) fn interact_inside(
+    response: Response,
+    sense: Sense,
+    contains: impl FnOnce(Pos2) -> bool,
+) -> Response {
+    if response.hover_pos().is_some_and(contains) || response.ctx.is_being_dragged(response.id) {
+        response.interact(sense)
+    } else {
+        response
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Navigation keys pressed this frame while a widget has keyboard focus.
+/// Opposing keys cancel each other out, `Right`/`Up` and `PageUp` count as
+/// positive steps.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct KeyboardSteps {
+    pub horizontal: i32,
+    pub vertical: i32,
+    pub page: i32,
+    pub home: bool,
+    pub end: bool,
+    pub shift: bool,
+}
+
+impl KeyboardSteps {
+    /// Reads the keys of a focused widget, and keeps arrow keys from moving
+    /// the focus to neighbouring widgets. Returns no steps without focus, or
+    /// for read-only and disabled widgets.
+    pub(crate) fn read(ui: &Ui, response: &Response) -> Self {
+        #[allow(unused_mut)]
+        let mut steps = Self::default();
+
+        if !response.sense.is_focusable() || !response.enabled() {
+            return steps;
+        }
+
+        // Assistive technologies may step a widget without focusing it first.
+        #[cfg(feature = "accesskit")]
+        ui.input(|input| {
+            use egui::accesskit::Action;
+
+            steps.horizontal += input.num_accesskit_action_requests(response.id, Action::Increment)
+                as i32
+                - input.num_accesskit_action_requests(response.id, Action::Decrement) as i32;
+        });
+
+        if !response.has_focus() {
+            return steps;
+        }
+
+        ui.memory_mut(|memory| {
+            memory.set_focus_lock_filter(
+                response.id,
+                EventFilter {
+                    horizontal_arrows: true,
+                    vertical_arrows: true,
+                    ..Default::default()
+                },
+            );
+        });
+
+        ui.input(|input| {
+            let presses = |key| input.num_presses(key) as i32;
+
+            Self {
+                horizontal: steps.horizontal + presses(Key::ArrowRight) - presses(Key::ArrowLeft),
+                vertical: presses(Key::ArrowUp) - presses(Key::ArrowDown),
+                page: presses(Key::PageUp) - presses(Key::PageDown),
+                home: input.key_pressed(Key::Home),
+                end: input.key_pressed(Key::End),
+                shift: input.modifiers.shift_only(),
+            }
+        })
+    }
+
+    /// Signed offset for one-dimensional widgets, where both arrow axes step
+    /// by `step` and the page keys by `page_step`.
+    pub(crate) fn offset(&self, step: f32, page_step: f32) -> f32 {
+        (self.horizontal + self.vertical) as f32 * step + self.page as f32 * page_step
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.horizontal == 0 && self.vertical == 0 && self.page == 0 && !self.home && !self.end
+    }
+}
+
+/// Mouse wheel and trackpad scrolling over a hovered widget, in wheel
+/// notches. Trackpads produce fractional notches.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ScrollSteps {
+    pub notches: f32,
+    pub fine: bool,
+    pub coarse: bool,
+}
+
+impl ScrollSteps {
+    const FINE_FACTOR: f32 = 0.1;
+    const COARSE_FACTOR: f32 = 10.0;
+
+    /// Reads the scrolling over a hovered widget, and consumes what it turned
+    /// into steps so that enclosing `ScrollArea`s stay put. Returns no steps
+    /// without hover, or for disabled widgets.
+    pub(crate) fn read(ui: &Ui, response: &Response) -> Self {
+        if !response.hovered() || !response.enabled() {
+            return Self::default();
+        }
+
+        let line_scroll_speed = ui.ctx().options(|options| options.line_scroll_speed);
+
+        ui.input_mut(|input| {
+            let mut steps = Self::default();
+
+            for event in &input.events {
+                if let Event::MouseWheel {
+                    unit,
+                    delta,
+                    modifiers,
+                } = event
+                {
+                    let delta = match unit {
+                        MouseWheelUnit::Point => *delta / line_scroll_speed,
+                        MouseWheelUnit::Line | MouseWheelUnit::Page => *delta,
+                    };
+
+                    // Shift turns vertical scrolling horizontal on some platforms
+                    steps.notches += delta.x + delta.y;
+                    steps.fine |= modifiers.command;
+                    steps.coarse |= modifiers.shift;
+                }
+            }
+
+            if !steps.is_empty() {
+                // Smoothing spreads wheel notches over several frames. Take
+                // no more than this frame's notches from it, and never turn
+                // what's left around.
+                let consumed = input.raw_scroll_delta;
+                let smooth = input.smooth_scroll_delta;
+                input.smooth_scroll_delta -=
+                    consumed.clamp(smooth.min(Vec2::ZERO), smooth.max(Vec2::ZERO));
+                input.raw_scroll_delta = Vec2::ZERO;
+            }
+
+            steps
+        })
+    }
+
+    /// Scrolls `value` by `step` per notch, or by `snap` per whole notch. The
+    /// fine and coarse modifiers scale the notches, except that the coarse
+    /// modifier scrolls by `coarse_snap` instead when there is one. Fractional
+    /// notches towards the next snap are kept under `id`. Non-positive snaps
+    /// are treated as no snap.
+    pub(crate) fn apply(
+        &self,
+        ui: &Ui,
+        id: Id,
+        value: f32,
+        step: f32,
+        snap: Option<f32>,
+        coarse_snap: Option<f32>,
+    ) -> f32 {
+        let positive = |snap: Option<f32>| snap.filter(|snap| *snap > 0.0);
+
+        let (snap, notches) = match positive(coarse_snap).filter(|_| self.coarse) {
+            Some(coarse_snap) => (Some(coarse_snap), self.notches),
+            None => (positive(snap), self.notches * self.factor()),
+        };
+
+        if let Some(snap) = snap {
+            let notches = ui.data(|data| data.get_temp::<f32>(id)).unwrap_or(0.0) + notches;
+            let whole_notches = notches.trunc();
+            ui.data_mut(|data| data.insert_temp(id, notches - whole_notches));
+
+            if whole_notches == 0.0 {
+                value
+            } else {
+                ((value / snap).round() + whole_notches) * snap
+            }
+        } else {
+            value + notches * step
+        }
+    }
+
+    /// Whether `apply` will snap with these snaps and modifiers.
+    #[cfg(feature = "knobs")]
+    pub(crate) fn snaps(&self, snap: Option<f32>, coarse_snap: Option<f32>) -> bool {
+        let positive = |snap: Option<f32>| snap.is_some_and(|snap| snap > 0.0);
+        positive(snap) || (self.coarse && positive(coarse_snap))
+    }
+
+    fn factor(&self) -> f32 {
+        if self.fine {
+            Self::FINE_FACTOR
+        } else if self.coarse {
+            Self::COARSE_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.notches == 0.0
+    }
+}
+
+/// Describes an angle-valued widget as a slider, with the value, range and
+/// step in `unit`. The range follows the limits or the wrap mode. Takes
+/// radians like the widgets work in internally.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn angle_slider_widget_info(
+    ui: &Ui,
+    response: &Response,
+    value: f32,
+    unit: AngleUnit,
+    wrap: WrapMode,
+    min: Option<f32>,
+    max: Option<f32>,
+    snap: Option<f32>,
+) {
+    let enabled = ui.is_enabled();
+    let value = unit.from_radians(value);
+
+    response.widget_info(|| WidgetInfo {
+        current_text_value: Some(unit.format(value)),
+        ..WidgetInfo::slider(enabled, f64::from(value), "")
+    });
+
+    let range = match (min, max, wrap) {
+        (Some(min), Some(max), _) => Some(min..=max),
+        (_, _, WrapMode::Signed) => Some(-PI..=PI),
+        (_, _, WrapMode::Unsigned) => Some(0.0..=TAU),
+        (_, _, WrapMode::None) => None,
+    }
+    .map(|range| unit.from_radians(*range.start())..=unit.from_radians(*range.end()));
+
+    accesskit_numeric_range(response, range, snap.map(|snap| unit.from_radians(snap)));
+}
+
+/// Focus rings are painted this far outside the widget outline.
+pub(crate) const FOCUS_RING_MARGIN: f32 = 2.0;
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod scroll_tests {
+    use super::*;
+
+    fn scroll(steps: &ScrollSteps, value: f32, snap: Option<f32>, coarse_snap: Option<f32>) -> f32 {
+        let ctx = egui::Context::default();
+        let mut new_value = value;
+
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                new_value = steps.apply(ui, Id::new("scroll"), value, 0.5, snap, coarse_snap);
+            });
+        });
+
+        new_value
+    }
+
+    #[test]
+    fn non_positive_snaps_are_no_snaps() {
+        let steps = ScrollSteps {
+            notches: 1.0,
+            ..Default::default()
+        };
+
+        assert_eq!(scroll(&steps, 1.0, Some(0.0), None), 1.5);
+        assert_eq!(scroll(&steps, 1.0, Some(-2.0), None), 1.5);
+        assert_eq!(scroll(&steps, 1.0, None, None), 1.5);
+    }
+
+    #[test]
+    fn modifiers_scale_snapped_notches() {
+        let plain = ScrollSteps {
+            notches: 1.0,
+            ..Default::default()
+        };
+        let fine = ScrollSteps {
+            fine: true,
+            ..plain
+        };
+        let coarse = ScrollSteps {
+            coarse: true,
+            ..plain
+        };
+
+        assert_eq!(scroll(&plain, 1.0, Some(1.0), None), 2.0);
+        assert_eq!(scroll(&fine, 1.0, Some(1.0), None), 1.0);
+        assert_eq!(scroll(&coarse, 1.0, Some(1.0), None), 11.0);
+
+        // The coarse snap takes the place of the coarse factor
+        assert_eq!(scroll(&coarse, 10.0, Some(1.0), Some(5.0)), 15.0);
+        assert_eq!(scroll(&plain, 10.0, Some(1.0), Some(5.0)), 11.0);
+    }
+
+    /// Reads the scrolling over a hovered widget in a frame with `events`,
+    /// after leftover smooth scrolling of `smooth_scroll_delta`. Returns the
+    /// notches and the scroll deltas left for the rest of the frame.
+    fn read(events: Vec<egui::Event>, smooth_scroll_delta: Vec2) -> (f32, Vec2, Vec2) {
+        let ctx = egui::Context::default();
+        let mut result = (0.0, Vec2::ZERO, Vec2::ZERO);
+
+        for events in [
+            vec![egui::Event::PointerMoved(egui::pos2(50.0, 50.0))],
+            events,
+        ] {
+            let input = egui::RawInput {
+                events,
+                ..Default::default()
+            };
+
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let response = ui.allocate_response(Vec2::splat(100.0), egui::Sense::hover());
+                    ui.input_mut(|input| input.smooth_scroll_delta += smooth_scroll_delta);
+
+                    let steps = ScrollSteps::read(ui, &response);
+                    result = ui.input(|input| {
+                        (
+                            steps.notches,
+                            input.raw_scroll_delta,
+                            input.smooth_scroll_delta,
+                        )
+                    });
+                });
+            });
+        }
+
+        result
+    }
+
+    #[test]
+    fn leaves_scrolling_without_notches_alone() {
+        let momentum = egui::vec2(0.0, 30.0);
+        assert_eq!(read(Vec::new(), momentum), (0.0, Vec2::ZERO, momentum));
+    }
+
+    #[test]
+    fn consumes_only_the_notches_it_read() {
+        let notch = egui::Event::MouseWheel {
+            unit: MouseWheelUnit::Point,
+            delta: egui::vec2(0.0, 4.0),
+            modifiers: egui::Modifiers::NONE,
+        };
+
+        let line_scroll_speed = egui::Options::default().line_scroll_speed;
+        let (notches, raw, smooth) = read(vec![notch], egui::vec2(0.0, 30.0));
+
+        assert_eq!(notches, 4.0 / line_scroll_speed);
+        assert_eq!(raw, Vec2::ZERO);
+        assert_eq!(smooth, egui::vec2(0.0, 30.0));
+    }
+}
@@ -0,0 +1,438 @@
+use std::f32::consts::TAU;
+
+use egui::{Align2, FontId, Painter, Rect};
+use emath::{Pos2, Rot2, Vec2};
+use epaint::{Color32, TextShape};
+
+use strum::{Display, EnumIter};
+
+mod animation;
+mod custom_shape;
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+mod inline_editor;
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+mod interaction;
+#[cfg(feature = "knobs")]
+mod outline;
+mod validation;
+mod widget_shape_parser;
+
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) use animation::animate_value;
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+pub(crate) use animation::reset_animation;
+pub use animation::{Animation, Easing};
+pub use custom_shape::{CustomShape, RadiusInterpolation};
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+pub(crate) use inline_editor::angle_inline_editor;
+#[cfg(feature = "knobs")]
+pub(crate) use inline_editor::{inline_editor, parse_with_unit};
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+pub(crate) use interaction::{
+    angle_slider_widget_info, interact_inside, KeyboardSteps, ScrollSteps, FOCUS_RING_MARGIN,
+};
+#[cfg(feature = "knobs")]
+pub(crate) use outline::paint_ellipse;
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) use validation::error_placeholder;
+#[cfg(feature = "knobs")]
+pub(crate) use validation::validate_normalized;
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+pub(crate) use validation::validate_snap;
+pub use validation::ValidationError;
+pub(crate) use validation::{validate_not_negative, validate_positive};
+pub use widget_shape_parser::{ParseWidgetShapeError, ParseWidgetShapeErrorKind};
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Orientation {
+    #[strum(to_string = "Top")]
+    Top,
+
+    #[strum(to_string = "Bottom")]
+    Bottom,
+
+    #[strum(to_string = "Left")]
+    Left,
+
+    #[strum(to_string = "Right")]
+    Right,
+
+    Custom(f32),
+}
+
+impl Orientation {
+    pub(crate) fn rot2(&self) -> Rot2 {
+        match *self {
+            Self::Right => Rot2::from_angle(TAU * 0.00),
+            Self::Bottom => Rot2::from_angle(TAU * 0.25),
+            Self::Left => Rot2::from_angle(TAU * 0.50),
+            Self::Top => Rot2::from_angle(TAU * 0.75),
+            Self::Custom(angle) => Rot2::from_angle(angle),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Winding {
+    #[strum(to_string = "Clockwise")]
+    Clockwise,
+
+    #[strum(to_string = "Counter-clockwise")]
+    Counterclockwise,
+}
+
+impl Winding {
+    pub(crate) fn to_float(self) -> f32 {
+        match self {
+            Self::Clockwise => 1.0,
+            Self::Counterclockwise => -1.0,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum WrapMode {
+    #[strum(to_string = "None")]
+    None,
+
+    #[strum(to_string = "Signed")]
+    Signed,
+
+    #[strum(to_string = "Unsigned")]
+    Unsigned,
+}
+
+// ----------------------------------------------------------------------------
+
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum WidgetShape {
+    Circle,
+    Square,
+    Squircle(f32),
+    Polygon(usize),
+    SuperPolygon(usize, f32),
+    Rotated(Box<WidgetShape>, f32),
+    Scaled(Box<WidgetShape>, f32),
+    Mix(Box<WidgetShape>, Box<WidgetShape>, f32),
+    Min(Box<WidgetShape>, Box<WidgetShape>),
+    Max(Box<WidgetShape>, Box<WidgetShape>),
+
+    /// Radius tables are serialized by value. Closures can't be, serializing a
+    /// shape built from a radius function fails.
+    Custom(CustomShape),
+}
+
+impl WidgetShape {
+    /// Checks the parameters of this shape and of the shapes it's built from.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match self {
+            WidgetShape::Circle | WidgetShape::Square | WidgetShape::Custom(_) => Ok(()),
+            WidgetShape::Squircle(factor) => validate_shape_factor(*factor),
+            WidgetShape::Polygon(n) => validate_polygon_sides(*n),
+            WidgetShape::SuperPolygon(n, factor) => {
+                validate_polygon_sides(*n)?;
+                validate_shape_factor(*factor)?;
+                if *factor > 2.0 {
+                    return Err(ValidationError::SuperPolygonFactor(*factor));
+                }
+                Ok(())
+            }
+            WidgetShape::Rotated(shape, _) | WidgetShape::Scaled(shape, _) => shape.validate(),
+            WidgetShape::Mix(shape_a, shape_b, _)
+            | WidgetShape::Min(shape_a, shape_b)
+            | WidgetShape::Max(shape_a, shape_b) => {
+                shape_a.validate()?;
+                shape_b.validate()
+            }
+        }
+    }
+
+    /// Expects a shape that passed `validate()`.
+    pub(crate) fn eval(&self, theta: f32) -> f32 {
+        match self {
+            WidgetShape::Circle => 1.0,
+            WidgetShape::Square => (1.0 / theta.cos().abs()).min(1.0 / theta.sin().abs()),
+            WidgetShape::Squircle(factor) => {
+                let a = theta.cos().abs().powf(*factor);
+                let b = theta.sin().abs().powf(*factor);
+                (a + b).powf(-1.0 / *factor)
+            }
+            WidgetShape::Polygon(n) => {
+                1.0 / ((*n as f32 / 2.0 * theta).cos().asin() * 2.0 / *n as f32).cos()
+            }
+            WidgetShape::SuperPolygon(n, factor) => {
+                // https://mathworld.wolfram.com/Superellipse.html
+                let a = (0.25 * (*n as f32) * theta).cos().abs().powf(*factor);
+                let b = (0.25 * (*n as f32) * theta).sin().abs().powf(*factor);
+                (a + b).powf(-1.0 / *factor)
+            }
+            WidgetShape::Rotated(shape, rotation) => shape.eval(theta - rotation),
+            WidgetShape::Scaled(shape, scale) => shape.eval(theta) * scale,
+            WidgetShape::Mix(shape_a, shape_b, t) => {
+                (shape_a.eval(theta) * (1.0 - t)) + (shape_b.eval(theta) * t)
+            }
+            WidgetShape::Min(shape_a, shape_b) => shape_a.eval(theta).min(shape_b.eval(theta)),
+            WidgetShape::Max(shape_a, shape_b) => shape_a.eval(theta).max(shape_b.eval(theta)),
+            WidgetShape::Custom(shape) => shape.eval(theta),
+        }
+    }
+
+    /// Tells whether `point` lies inside the outline painted with the same
+    /// `center`, `radius` and `rotation`.
+    #[must_use]
+    pub fn contains(&self, center: Pos2, radius: f32, rotation: Rot2, point: Pos2) -> bool {
+        let offset = point - center;
+        let rotation_angle = (rotation * Vec2::RIGHT).angle();
+
+        offset.length() <= radius * self.eval(offset.angle() - rotation_angle)
+    }
+
+    /// Tells whether `point` lies inside the ring segment painted with the
+    /// same radii, angles and `rotation`.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn contains_arc(
+        &self,
+        center: Pos2,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        rotation: Rot2,
+        point: Pos2,
+    ) -> bool {
+        let offset = point - center;
+        let rotation_angle = (rotation * Vec2::RIGHT).angle();
+
+        let angle_span = (end_angle - start_angle).abs();
+        let angle_offset = (offset.angle() - start_angle.min(end_angle)).rem_euclid(TAU);
+
+        let shape_radius = self.eval(offset.angle() - rotation_angle);
+
+        (angle_span >= TAU || angle_offset <= angle_span)
+            && ((inner_radius * shape_radius)..=(outer_radius * shape_radius))
+                .contains(&offset.length())
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+fn validate_shape_factor(factor: f32) -> Result<(), ValidationError> {
+    if factor > 0.0 {
+        Ok(())
+    } else {
+        Err(ValidationError::ShapeFactor(factor))
+    }
+}
+
+fn validate_polygon_sides(n: usize) -> Result<(), ValidationError> {
+    if n >= 3 {
+        Ok(())
+    } else {
+        Err(ValidationError::PolygonSides(n))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Whether a widget configured as `interactive` takes input in `ui`. Widgets
+/// in a disabled `Ui` ignore input like read-only ones do, but unlike those
+/// they're still greyed out, see `interact_visuals()`.
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) fn accepts_input(ui: &egui::Ui, interactive: bool) -> bool {
+    interactive && ui.is_enabled()
+}
+
+/// Like `Style::interact()`, but widgets that are `interactive` and only
+/// sense hover because of `interact_inside()` are painted inactive rather
+/// than non-interactive.
+///
+/// Widgets in a disabled `Ui` are painted inactive as well, and greyed out
+/// by its painter, so they look distinct from read-only widgets.
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) fn interact_visuals(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    interactive: bool,
+) -> egui::style::WidgetVisuals {
+    if !ui.is_enabled() || (interactive && !response.sense.interactive()) {
+        ui.style().visuals.widgets.inactive
+    } else {
+        *ui.style().interact(response)
+    }
+}
+
+/// Reports the value limits and step of a slider-like widget to AccessKit,
+/// on top of what `Response::widget_info()` exposes.
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+#[cfg_attr(not(feature = "accesskit"), allow(unused_variables))]
+pub(crate) fn accesskit_numeric_range(
+    response: &egui::Response,
+    range: Option<std::ops::RangeInclusive<f32>>,
+    step: Option<f32>,
+) {
+    #[cfg(feature = "accesskit")]
+    response.ctx.accesskit_node_builder(response.id, |node| {
+        if let Some(range) = range {
+            node.set_min_numeric_value(f64::from(*range.start()));
+            node.set_max_numeric_value(f64::from(*range.end()));
+        }
+
+        if let Some(step) = step {
+            node.set_numeric_value_step(f64::from(step));
+        }
+    });
+}
+
+// ----------------------------------------------------------------------------
+// TODO: Remove this trait when egui exposes text rotation with a sane API.
+
+pub(crate) trait RotatedText {
+    fn rotated_text(
+        &self,
+        pos: Pos2,
+        anchor: Align2,
+        text: impl ToString,
+        font_id: FontId,
+        text_color: Color32,
+        angle: f32,
+    ) -> Rect;
+}
+
+impl RotatedText for Painter {
+    fn rotated_text(
+        &self,
+        pos: Pos2,
+        anchor: Align2,
+        text: impl ToString,
+        font_id: FontId,
+        text_color: Color32,
+        angle: f32,
+    ) -> Rect {
+        let galley = self.layout_no_wrap(text.to_string(), font_id, text_color);
+        let rect = anchor.anchor_rect(Rect::from_min_size(pos, galley.size()));
+
+        let half_size = galley.size() / 2.0;
+
+        self.add(TextShape {
+            angle,
+            override_text_color: Some(text_color),
+            ..TextShape::new(
+                pos - Rot2::from_angle(angle) * (half_size + (anchor.to_sign() * half_size)),
+                galley,
+                text_color,
+            )
+        });
+
+        rect
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Runs a frame with AccessKit enabled and returns the node emitted for the
+/// widget `add_widget` adds.
+#[cfg(all(
+    test,
+    feature = "accesskit",
+    any(
+        feature = "barcodes",
+        feature = "compasses",
+        feature = "displays",
+        feature = "knobs"
+    )
+))]
+pub(crate) fn accesskit_node(
+    mut add_widget: impl FnMut(&mut egui::Ui) -> egui::Response,
+) -> egui::accesskit::Node {
+    let ctx = egui::Context::default();
+    ctx.enable_accesskit();
+
+    let mut widget_id = None;
+    let output = ctx.run(egui::RawInput::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            widget_id = Some(add_widget(ui).id);
+        });
+    });
+
+    let node_id = egui::accesskit::NodeId(widget_id.unwrap().value());
+    output
+        .platform_output
+        .accesskit_update
+        .unwrap()
+        .nodes
+        .into_iter()
+        .find_map(|(id, node)| (id == node_id).then_some(node))
+        .unwrap()
+}
+
+/// Serializes `value` to JSON and back, and checks that nothing got lost.
+#[cfg(all(test, feature = "serde"))]
+pub(crate) fn assert_serde_round_trip<T>(value: T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value, "{json}");
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for orientation in [
+            Orientation::Top,
+            Orientation::Bottom,
+            Orientation::Left,
+            Orientation::Right,
+            Orientation::Custom(1.25),
+        ] {
+            assert_serde_round_trip(orientation);
+        }
+
+        Winding::iter().for_each(assert_serde_round_trip);
+        WrapMode::iter().for_each(assert_serde_round_trip);
+
+        assert_serde_round_trip(WidgetShape::Max(
+            Box::new(WidgetShape::Mix(
+                Box::new(WidgetShape::Squircle(4.0)),
+                Box::new(WidgetShape::Rotated(Box::new(WidgetShape::Polygon(6)), 0.5)),
+                0.25,
+            )),
+            Box::new(WidgetShape::Min(
+                Box::new(WidgetShape::Scaled(Box::new(WidgetShape::Square), 0.75)),
+                Box::new(WidgetShape::Custom(CustomShape::from_radius_table(
+                    [1.0, 0.8, 0.9],
+                    RadiusInterpolation::Cubic,
+                ))),
+            )),
+        ));
+    }
+
+    #[test]
+    fn radius_functions_fail_to_serialize() {
+        let shape = WidgetShape::Min(
+            Box::new(WidgetShape::Circle),
+            Box::new(WidgetShape::Custom(CustomShape::from_fn(|_| 1.0))),
+        );
+
+        assert!(serde_json::to_string(&shape).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use std::f32::consts::{PI, TAU};
+
+use egui::Ui;
+use emath::{Pos2, Rot2, Vec2};
+use epaint::{Color32, Mesh, Shape, Stroke};
+use itertools::Itertools;
+
+use crate::common::WidgetShape;
+
+// ----------------------------------------------------------------------------
+
+impl WidgetShape {
+    /// Maximum distance between the sampled and the exact outline, in physical pixels.
+    const TOLERANCE: f32 = 0.25;
+
+    /// Maximum number of times an outline segment gets halved to follow curvature.
+    const MAX_SUBDIVISIONS: usize = 6;
+
+    /// Angles of the sharp corners of the outline, in shape space, modulo `TAU`.
+    fn corner_angles(&self) -> Vec<f32> {
+        let evenly_spaced = |count: usize, phase: f32| {
+            (0..count)
+                .map(|index| phase + (index as f32 / count as f32) * TAU)
+                .collect_vec()
+        };
+
+        match self {
+            WidgetShape::Circle => Vec::new(),
+            WidgetShape::Square => evenly_spaced(4, TAU / 8.0),
+            WidgetShape::Squircle(factor) if *factor <= 1.0 => evenly_spaced(4, 0.0),
+            WidgetShape::Squircle(_) => Vec::new(),
+            WidgetShape::Polygon(n) => evenly_spaced(*n, 0.0),
+            WidgetShape::SuperPolygon(n, factor) if *factor <= 1.0 => evenly_spaced(*n, 0.0),
+            WidgetShape::SuperPolygon(..) => Vec::new(),
+            WidgetShape::Rotated(shape, rotation) => shape
+                .corner_angles()
+                .into_iter()
+                .map(|angle| angle + rotation)
+                .collect(),
+            WidgetShape::Scaled(shape, _) => shape.corner_angles(),
+            // Kinks where the two shapes of `Min` and `Max` cross are left
+            // to the curvature-based subdivision.
+            WidgetShape::Mix(shape_a, shape_b, _)
+            | WidgetShape::Min(shape_a, shape_b)
+            | WidgetShape::Max(shape_a, shape_b) => {
+                let mut corners = shape_a.corner_angles();
+                corners.extend(shape_b.corner_angles());
+                corners
+            }
+            WidgetShape::Custom(shape) => shape.corner_angles(),
+        }
+    }
+
+    /// Outline sample angles from `start_angle` to `end_angle` (both included,
+    /// in either direction), in screen space. The sample count follows the
+    /// on-screen radius and the curvature of the shape, and sharp corners get
+    /// a sample exactly at the corner.
+    fn outline_angles(
+        &self,
+        start_angle: f32,
+        end_angle: f32,
+        rotation_angle: f32,
+        radius_in_pixels: f32,
+    ) -> Vec<f32> {
+        let span = end_angle - start_angle;
+
+        let outline_point = |angle: f32| {
+            Pos2::ZERO + Vec2::angled(angle) * radius_in_pixels * self.eval(angle - rotation_angle)
+        };
+
+        let mut breakpoints = {
+            let segment_count = (circle_segment_count(radius_in_pixels) as f32 * span.abs() / TAU)
+                .ceil()
+                .max(1.0) as usize;
+
+            (0..=segment_count)
+                .map(|index| start_angle + span * (index as f32 / segment_count as f32))
+                .collect_vec()
+        };
+
+        let (range_min, range_max) = (start_angle.min(end_angle), start_angle.max(end_angle));
+
+        for corner_angle in self.corner_angles() {
+            let corner_angle = corner_angle + rotation_angle;
+            let first_turn = ((range_min - corner_angle) / TAU).ceil() as isize;
+            let last_turn = ((range_max - corner_angle) / TAU).floor() as isize;
+
+            for turn in first_turn..=last_turn {
+                breakpoints.push(corner_angle + turn as f32 * TAU);
+            }
+        }
+
+        breakpoints.sort_by(|a, b| (a - start_angle).abs().total_cmp(&(b - start_angle).abs()));
+        breakpoints.dedup_by(|a, b| (*a - *b).abs() < 1e-4);
+
+        if breakpoints.len() < 2 {
+            // Zero-length arcs still need both ends, they're painted as a line.
+            breakpoints.push(end_angle);
+        }
+
+        let mut angles = vec![start_angle];
+
+        for (&angle_a, &angle_b) in breakpoints.iter().tuple_windows() {
+            let mut pending = vec![(angle_b, 0)];
+            let mut angle_a = angle_a;
+
+            // Depth-first subdivision, emitting angles in order.
+            while let Some((angle_b, depth)) = pending.pop() {
+                let angle_mid = (angle_a + angle_b) / 2.0;
+
+                let deviation = distance_to_segment(
+                    outline_point(angle_mid),
+                    outline_point(angle_a),
+                    outline_point(angle_b),
+                );
+
+                if depth < Self::MAX_SUBDIVISIONS && deviation > Self::TOLERANCE {
+                    pending.push((angle_b, depth + 1));
+                    pending.push((angle_mid, depth + 1));
+                } else {
+                    angles.push(angle_b);
+                    angle_a = angle_b;
+                }
+            }
+        }
+
+        angles
+    }
+
+    pub(crate) fn paint_shape(
+        &self,
+        ui: &mut Ui,
+        center: Pos2,
+        radius: f32,
+        fill: Color32,
+        stroke: Stroke,
+        rotation: Rot2,
+    ) {
+        let rotation_angle = (rotation * Vec2::RIGHT).angle();
+        let radius_in_pixels = radius * ui.ctx().pixels_per_point();
+
+        let mut outline_angles = self.outline_angles(
+            rotation_angle,
+            rotation_angle + TAU,
+            rotation_angle,
+            radius_in_pixels,
+        );
+        outline_angles.pop(); // Same point as the first one
+
+        let outline_points = outline_angles
+            .into_iter()
+            .map(|angle| center + Vec2::angled(angle) * radius * self.eval(angle - rotation_angle))
+            .collect_vec();
+
+        ui.painter().add(Shape::mesh(tessellate_outline(
+            &outline_points,
+            OutlineFill::Fan(center),
+            fill,
+            stroke,
+            feathering_size(ui),
+        )));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn paint_arc(
+        &self,
+        ui: &mut Ui,
+        center: Pos2,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        fill: Color32,
+        stroke: Stroke,
+        rotation: Rot2,
+    ) {
+        let rotation_angle = (rotation * Vec2::RIGHT).angle();
+        let radius_in_pixels = outer_radius * ui.ctx().pixels_per_point();
+
+        let arc_angles =
+            self.outline_angles(start_angle, end_angle, rotation_angle, radius_in_pixels);
+
+        let generate_arc_points = |radius| {
+            arc_angles.iter().map(move |&angle| {
+                center + Vec2::angled(angle) * radius * self.eval(angle - rotation_angle)
+            })
+        };
+
+        // Outer arc forward, inner arc backward. Point `i` of the outer arc
+        // and point `len - 1 - i` of the inner arc lie on the same ray.
+        let outline_points = generate_arc_points(outer_radius)
+            .chain(generate_arc_points(inner_radius).rev())
+            .collect_vec();
+
+        ui.painter().add(Shape::mesh(tessellate_outline(
+            &outline_points,
+            OutlineFill::Strip,
+            fill,
+            stroke,
+            feathering_size(ui),
+        )));
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Every `WidgetShape` outline is a polar function around its center, hence
+// star-shaped, even when it's concave (`Min`, `Max`, `SuperPolygon`, ...).
+// This lets us triangulate fills as a fan around the center (shapes) or as a
+// strip between pairs of points sharing the same ray (arcs), and emit the
+// fill, the stroke and their anti-aliasing feathers as a single mesh.
+
+#[derive(Clone, Copy)]
+enum OutlineFill {
+    /// Triangle fan around the given point.
+    Fan(Pos2),
+
+    /// Quad strip between the first and the mirrored second half of the outline.
+    Strip,
+}
+
+/// Feathering width in points, as configured in the tessellation options.
+fn feathering_size(ui: &Ui) -> f32 {
+    let pixels_per_point = ui.ctx().pixels_per_point();
+
+    ui.ctx().tessellation_options(|options| {
+        if options.feathering {
+            options.feathering_size_in_pixels / pixels_per_point
+        } else {
+            0.0
+        }
+    })
+}
+
+/// Outward-facing vertex normals of a closed outline, regardless of its winding.
+fn outline_normals(points: &[Pos2]) -> Vec<Vec2> {
+    let signed_area: f32 = points
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+
+    let orientation = if signed_area < 0.0 { -1.0 } else { 1.0 };
+
+    let edge_normals = points
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| (*b - *a).normalized().rot90() * orientation)
+        .collect_vec();
+
+    (0..points.len())
+        .map(|index| {
+            let prev_normal = edge_normals[(index + points.len() - 1) % points.len()];
+            let next_normal = edge_normals[index];
+
+            // Miter joins, except on very sharp or degenerate corners where
+            // the miter length would blow up.
+            let normal = (prev_normal + next_normal) / 2.0;
+            if normal.length_sq() > 0.25 {
+                normal / normal.length_sq()
+            } else {
+                normal.normalized()
+            }
+        })
+        .collect()
+}
+
+/// Adds a ring of vertices for every `(offset, color)` lane along the outline
+/// and connects neighbouring lanes with quads. Returns the index of the first
+/// vertex, the vertex of point `i` on lane `k` is `first + i * lanes.len() + k`.
+fn add_outline_band(
+    mesh: &mut Mesh,
+    points: &[Pos2],
+    normals: &[Vec2],
+    lanes: &[(f32, Color32)],
+) -> u32 {
+    let first_index = mesh.vertices.len() as u32;
+    let lane_count = lanes.len() as u32;
+    let point_count = points.len() as u32;
+
+    for (point, normal) in points.iter().zip(normals) {
+        for &(offset, color) in lanes {
+            mesh.colored_vertex(*point + *normal * offset, color);
+        }
+    }
+
+    for point_a in 0..point_count {
+        let point_b = (point_a + 1) % point_count;
+
+        for lane in 0..lane_count.saturating_sub(1) {
+            let a0 = first_index + point_a * lane_count + lane;
+            let b0 = first_index + point_b * lane_count + lane;
+
+            mesh.add_triangle(a0, b0, a0 + 1);
+            mesh.add_triangle(a0 + 1, b0, b0 + 1);
+        }
+    }
+
+    first_index
+}
+
+fn tessellate_outline(
+    points: &[Pos2],
+    outline_fill: OutlineFill,
+    fill: Color32,
+    stroke: Stroke,
+    feathering: f32,
+) -> Mesh {
+    let mut mesh = Mesh::default();
+
+    if points.len() < 3 {
+        return mesh;
+    }
+
+    let normals = outline_normals(points);
+    let point_count = points.len() as u32;
+
+    if fill != Color32::TRANSPARENT {
+        let fill_lanes = if feathering > 0.0 {
+            vec![
+                (-feathering / 2.0, fill),
+                (feathering / 2.0, Color32::TRANSPARENT),
+            ]
+        } else {
+            vec![(0.0, fill)]
+        };
+
+        let first_index = add_outline_band(&mut mesh, points, &normals, &fill_lanes);
+        let inner_vertex = |point: u32| first_index + point * fill_lanes.len() as u32;
+
+        match outline_fill {
+            OutlineFill::Fan(center) => {
+                let center_index = mesh.vertices.len() as u32;
+                mesh.colored_vertex(center, fill);
+
+                for point_a in 0..point_count {
+                    let point_b = (point_a + 1) % point_count;
+                    mesh.add_triangle(center_index, inner_vertex(point_a), inner_vertex(point_b));
+                }
+            }
+            OutlineFill::Strip => {
+                for outer_a in 0..(point_count / 2).saturating_sub(1) {
+                    let outer_b = outer_a + 1;
+                    let (inner_a, inner_b) = (point_count - 1 - outer_a, point_count - 1 - outer_b);
+
+                    mesh.add_triangle(
+                        inner_vertex(outer_a),
+                        inner_vertex(outer_b),
+                        inner_vertex(inner_a),
+                    );
+                    mesh.add_triangle(
+                        inner_vertex(outer_b),
+                        inner_vertex(inner_b),
+                        inner_vertex(inner_a),
+                    );
+                }
+            }
+        }
+    }
+
+    if !stroke.is_empty() {
+        let half_width = stroke.width / 2.0;
+
+        let stroke_lanes = if feathering <= 0.0 {
+            vec![(-half_width, stroke.color), (half_width, stroke.color)]
+        } else if stroke.width <= feathering {
+            // Thin strokes fade out instead of getting thinner than a pixel.
+            let color = stroke.color.gamma_multiply(stroke.width / feathering);
+            vec![
+                (-feathering, Color32::TRANSPARENT),
+                (0.0, color),
+                (feathering, Color32::TRANSPARENT),
+            ]
+        } else {
+            vec![
+                (-half_width - feathering / 2.0, Color32::TRANSPARENT),
+                (-half_width + feathering / 2.0, stroke.color),
+                (half_width - feathering / 2.0, stroke.color),
+                (half_width + feathering / 2.0, Color32::TRANSPARENT),
+            ]
+        };
+
+        add_outline_band(&mut mesh, points, &normals, &stroke_lanes);
+    }
+
+    mesh
+}
+
+// ----------------------------------------------------------------------------
+
+/// Number of segments needed to keep a circle's polygonal approximation
+/// within `WidgetShape::TOLERANCE` of the exact circle.
+fn circle_segment_count(radius_in_pixels: f32) -> usize {
+    // Chord error: r * (1 - cos(PI / n)) ~= r * PI^2 / (2 * n^2)
+    let segment_count = PI * (radius_in_pixels / (2.0 * WidgetShape::TOLERANCE)).sqrt();
+    (segment_count.ceil() as usize).clamp(8, 1024)
+}
+
+fn distance_to_segment(point: Pos2, segment_a: Pos2, segment_b: Pos2) -> f32 {
+    let segment = segment_b - segment_a;
+
+    let t = if segment.length_sq() > 0.0 {
+        ((point - segment_a).dot(segment) / segment.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    point.distance(segment_a + segment * t)
+}
+
+// ----------------------------------------------------------------------------
+
+pub(crate) fn paint_ellipse(
+    ui: &mut Ui,
+    center: Pos2,
+    size: Vec2,
+    fill: Color32,
+    stroke: Stroke,
+    rotation: Rot2,
+) {
+    let segment_count = circle_segment_count(size.max_elem() / 2.0 * ui.ctx().pixels_per_point());
+
+    let points = (0..segment_count)
+        .map(|i| ((i as f32) / (segment_count as f32)) * TAU)
+        .map(|t| center + rotation * (Vec2::angled(t) * (size / 2.0)))
+        .collect();
+
+    ui.painter()
+        .add(Shape::convex_polygon(points, fill, stroke));
+}
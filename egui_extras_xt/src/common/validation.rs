@@ -0,0 +1,215 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+// ----------------------------------------------------------------------------
+
+/// Invalid widget configuration, as reported by the `validate()` methods and
+/// the `try_` builders.
+///
+/// Widgets that are given an invalid configuration show the error in place
+/// of themselves instead of panicking.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// Squircle and super polygon factors must be positive.
+    ShapeFactor(f32),
+
+    /// Super polygon factors must be at most 2.0.
+    SuperPolygonFactor(f32),
+
+    /// Polygons need at least 3 sides.
+    PolygonSides(usize),
+
+    /// Star-shaped markers need at least 2 rays.
+    StarRays(usize),
+
+    /// Star ray ratios must be within `0.0..=1.0`.
+    StarRatio(f32),
+
+    /// Snap angles and values must be positive.
+    Snap(f32),
+
+    /// Logarithmic tapers can't cross or touch zero.
+    LogarithmicTaperRange { start: f32, end: f32 },
+
+    /// Decibel tapers need a negative floor and a range ending above zero.
+    DecibelTaper { min_db: f32, end: f32 },
+
+    /// Thumbstick snapping needs at least one axis.
+    ThumbstickSnapAxes(usize),
+
+    /// Thresholds, dead zones and similar parameters must be within `0.0..=1.0`.
+    NotNormalized { name: &'static str, value: f32 },
+
+    /// Sizes and similar parameters that must be positive.
+    NotPositive { name: &'static str, value: f32 },
+
+    /// Distances and similar parameters that can't be negative.
+    Negative { name: &'static str, value: f32 },
+
+    /// Polar compass log scales need a finite base above 1.0.
+    LogBase(f32),
+
+    /// Polar compass rings would be too many to paint.
+    RingCount(f32),
+
+    /// Markers on polar compasses need a distance.
+    MarkerDistance,
+
+    /// Compasses take exactly 4 axis labels.
+    AxisLabelCount(usize),
+
+    /// Waveforms need at least one channel.
+    ChannelCount(usize),
+
+    /// Waveform buffers must hold a whole number of frames.
+    BufferLength { length: usize, channels: usize },
+
+    /// Waveforms need an even number of samples per channel, at least 4.
+    ChannelLength(usize),
+
+    /// Waveform windows must be even and fit within the channel.
+    WindowSize {
+        window_size: usize,
+        channel_length: usize,
+    },
+
+    /// Waveform channel names must match the channel count.
+    ChannelNameCount { names: usize, channels: usize },
+
+    /// Waveform resolutions must be at least 1.
+    WaveformResolution(usize),
+
+    /// Waveforms can only trigger on one of their channels.
+    TriggerChannel { channel: usize, channels: usize },
+
+    /// Waveform peaks must be built from a buffer of the same shape.
+    PeaksMismatch,
+
+    /// XY waveforms plot channels in pairs.
+    XyChannelCount(usize),
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ShapeFactor(factor) => write!(f, "shape factor must be positive, got {factor}"),
+            Self::SuperPolygonFactor(factor) => {
+                write!(f, "super polygon factor must be at most 2.0, got {factor}")
+            }
+            Self::PolygonSides(n) => write!(f, "polygon must have at least 3 sides, got {n}"),
+            Self::StarRays(rays) => write!(f, "star must have at least 2 rays, got {rays}"),
+            Self::StarRatio(ratio) => write!(f, "star ray ratio must be normalized, got {ratio}"),
+            Self::Snap(snap) => write!(f, "snap must be positive, got {snap}"),
+            Self::LogarithmicTaperRange { start, end } => write!(
+                f,
+                "logarithmic taper range must not contain zero, got {start}..={end}"
+            ),
+            Self::DecibelTaper { min_db, end } => write!(
+                f,
+                "decibel taper needs a negative floor and a range ending above zero, \
+                 got {min_db} dB and {end}"
+            ),
+            Self::ThumbstickSnapAxes(axes) => {
+                write!(f, "thumbstick snap must have at least 1 axis, got {axes}")
+            }
+            Self::NotNormalized { name, value } => {
+                write!(f, "{name} must be normalized, got {value}")
+            }
+            Self::NotPositive { name, value } => write!(f, "{name} must be positive, got {value}"),
+            Self::Negative { name, value } => write!(f, "{name} must not be negative, got {value}"),
+            Self::LogBase(base) => write!(f, "log scale base must be above 1.0, got {base}"),
+            Self::RingCount(rings) => write!(f, "too many rings to paint ({rings})"),
+            Self::MarkerDistance => write!(f, "polar compass markers must have a distance"),
+            Self::AxisLabelCount(count) => {
+                write!(f, "compass must have exactly 4 axis labels, got {count}")
+            }
+            Self::ChannelCount(channels) => {
+                write!(f, "waveform must have at least 1 channel, got {channels}")
+            }
+            Self::BufferLength { length, channels } => write!(
+                f,
+                "buffer length {length} is not a multiple of the channel count {channels}"
+            ),
+            Self::ChannelLength(length) => {
+                write!(
+                    f,
+                    "channel length must be even and at least 4, got {length}"
+                )
+            }
+            Self::WindowSize {
+                window_size,
+                channel_length,
+            } => write!(
+                f,
+                "window size must be even and at most {channel_length}, got {window_size}"
+            ),
+            Self::ChannelNameCount { names, channels } => {
+                write!(f, "got {names} channel names for {channels} channels")
+            }
+            Self::WaveformResolution(resolution) => {
+                write!(
+                    f,
+                    "waveform resolution must be at least 1, got {resolution}"
+                )
+            }
+            Self::TriggerChannel { channel, channels } => {
+                write!(f, "trigger channel {channel} is out of {channels} channels")
+            }
+            Self::PeaksMismatch => write!(f, "waveform peaks don't match the buffer"),
+            Self::XyChannelCount(channels) => {
+                write!(
+                    f,
+                    "XY waveform needs an even number of channels, got {channels}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(any(feature = "compasses", feature = "knobs"))]
+pub(crate) fn validate_snap(snap: Option<f32>) -> Result<(), ValidationError> {
+    match snap {
+        Some(snap) if snap <= 0.0 || snap.is_nan() => Err(ValidationError::Snap(snap)),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_positive(name: &'static str, value: f32) -> Result<(), ValidationError> {
+    if value > 0.0 {
+        Ok(())
+    } else {
+        Err(ValidationError::NotPositive { name, value })
+    }
+}
+
+#[cfg(feature = "knobs")]
+pub(crate) fn validate_normalized(name: &'static str, value: f32) -> Result<(), ValidationError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(ValidationError::NotNormalized { name, value })
+    }
+}
+
+pub(crate) fn validate_not_negative(name: &'static str, value: f32) -> Result<(), ValidationError> {
+    if value >= 0.0 {
+        Ok(())
+    } else {
+        Err(ValidationError::Negative { name, value })
+    }
+}
+
+/// Shown by widgets in place of themselves when their configuration is
+/// invalid.
+#[cfg(any(feature = "compasses", feature = "displays", feature = "knobs"))]
+pub(crate) fn error_placeholder(ui: &mut egui::Ui, error: &ValidationError) -> egui::Response {
+    ui.colored_label(
+        ui.style().visuals.error_fg_color,
+        format!("\u{1F525} {error}"),
+    )
+}
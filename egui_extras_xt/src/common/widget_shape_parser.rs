@@ -0,0 +1,667 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::common::{CustomShape, RadiusInterpolation, WidgetShape};
+
+// ----------------------------------------------------------------------------
+
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseWidgetShapeErrorKind {
+    UnexpectedEnd,
+    UnexpectedCharacter(char),
+    TrailingCharacters,
+    UnknownShape(String),
+    InvalidNumber(String),
+    InvalidInteger(String),
+    ExpectedShape,
+    ExpectedNumber,
+    TooDeeplyNested,
+    WrongArgumentCount {
+        shape: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for ParseWidgetShapeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            Self::UnexpectedCharacter(c) => write!(f, "unexpected character '{c}'"),
+            Self::TrailingCharacters => write!(f, "unexpected characters after the shape"),
+            Self::UnknownShape(name) => write!(f, "unknown shape '{name}'"),
+            Self::InvalidNumber(text) => write!(f, "invalid number '{text}'"),
+            Self::InvalidInteger(text) => write!(f, "invalid integer '{text}'"),
+            Self::ExpectedShape => write!(f, "expected a shape"),
+            Self::ExpectedNumber => write!(f, "expected a number"),
+            Self::TooDeeplyNested => {
+                write!(f, "shapes nested deeper than {} levels", Parser::MAX_DEPTH)
+            }
+            Self::WrongArgumentCount {
+                shape,
+                expected,
+                found,
+            } => write!(f, "'{shape}' takes {expected} argument(s), found {found}"),
+        }
+    }
+}
+
+/// Error returned when parsing a `WidgetShape` expression fails.
+/// `position` is the byte offset into the source string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseWidgetShapeError {
+    pub kind: ParseWidgetShapeErrorKind,
+    pub position: usize,
+}
+
+impl Display for ParseWidgetShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl Error for ParseWidgetShapeError {}
+
+// ----------------------------------------------------------------------------
+
+enum Argument<'a> {
+    Shape(WidgetShape),
+    Number(&'a str),
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Shapes nested deeper than this are rejected rather than overflowing
+    /// the stack.
+    const MAX_DEPTH: usize = 64;
+
+    fn error<T>(
+        &self,
+        kind: ParseWidgetShapeErrorKind,
+        position: usize,
+    ) -> Result<T, ParseWidgetShapeError> {
+        Err(ParseWidgetShapeError { kind, position })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let start = self.position;
+        while let Some(c) = self.peek().filter(|&c| predicate(c)) {
+            self.position += c.len_utf8();
+        }
+        &self.source[start..self.position]
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseWidgetShapeError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => self.error(
+                ParseWidgetShapeErrorKind::UnexpectedCharacter(c),
+                self.position,
+            ),
+            None => self.error(ParseWidgetShapeErrorKind::UnexpectedEnd, self.position),
+        }
+    }
+
+    fn parse_expression(mut self) -> Result<WidgetShape, ParseWidgetShapeError> {
+        let shape = self.parse_shape()?;
+
+        self.skip_whitespace();
+        if self.position < self.source.len() {
+            return self.error(ParseWidgetShapeErrorKind::TrailingCharacters, self.position);
+        }
+
+        Ok(shape)
+    }
+
+    fn parse_shape(&mut self) -> Result<WidgetShape, ParseWidgetShapeError> {
+        self.skip_whitespace();
+
+        let name_position = self.position;
+        let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        if name.is_empty() {
+            return match self.peek() {
+                Some(_) => self.error(ParseWidgetShapeErrorKind::ExpectedShape, name_position),
+                None => self.error(ParseWidgetShapeErrorKind::UnexpectedEnd, name_position),
+            };
+        }
+
+        let arguments = self.parse_arguments()?;
+
+        match name {
+            "circle" => {
+                let [] = self.arguments("circle", arguments, name_position)?;
+                Ok(WidgetShape::Circle)
+            }
+            "square" => {
+                let [] = self.arguments("square", arguments, name_position)?;
+                Ok(WidgetShape::Square)
+            }
+            "squircle" => {
+                let [factor] = self.arguments("squircle", arguments, name_position)?;
+                Ok(WidgetShape::Squircle(self.number_argument(factor)?))
+            }
+            "polygon" => {
+                let [n] = self.arguments("polygon", arguments, name_position)?;
+                Ok(WidgetShape::Polygon(self.integer_argument(n)?))
+            }
+            "super_polygon" => {
+                let [n, factor] = self.arguments("super_polygon", arguments, name_position)?;
+                Ok(WidgetShape::SuperPolygon(
+                    self.integer_argument(n)?,
+                    self.number_argument(factor)?,
+                ))
+            }
+            "rotated" => {
+                let [shape, rotation] = self.arguments("rotated", arguments, name_position)?;
+                Ok(WidgetShape::Rotated(
+                    self.shape_argument(shape)?,
+                    self.number_argument(rotation)?,
+                ))
+            }
+            "scaled" => {
+                let [shape, scale] = self.arguments("scaled", arguments, name_position)?;
+                Ok(WidgetShape::Scaled(
+                    self.shape_argument(shape)?,
+                    self.number_argument(scale)?,
+                ))
+            }
+            "mix" => {
+                let [shape_a, shape_b, t] = self.arguments("mix", arguments, name_position)?;
+                Ok(WidgetShape::Mix(
+                    self.shape_argument(shape_a)?,
+                    self.shape_argument(shape_b)?,
+                    self.number_argument(t)?,
+                ))
+            }
+            "min" => {
+                let [shape_a, shape_b] = self.arguments("min", arguments, name_position)?;
+                Ok(WidgetShape::Min(
+                    self.shape_argument(shape_a)?,
+                    self.shape_argument(shape_b)?,
+                ))
+            }
+            "max" => {
+                let [shape_a, shape_b] = self.arguments("max", arguments, name_position)?;
+                Ok(WidgetShape::Max(
+                    self.shape_argument(shape_a)?,
+                    self.shape_argument(shape_b)?,
+                ))
+            }
+            "linear_table" | "cubic_table" => {
+                let interpolation = if name == "linear_table" {
+                    RadiusInterpolation::Linear
+                } else {
+                    RadiusInterpolation::Cubic
+                };
+
+                if arguments.is_empty() {
+                    return self.error(ParseWidgetShapeErrorKind::ExpectedNumber, self.position);
+                }
+
+                let radii = arguments
+                    .into_iter()
+                    .map(|argument| self.number_argument(argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(WidgetShape::Custom(CustomShape::from_radius_table(
+                    radii,
+                    interpolation,
+                )))
+            }
+            _ => self.error(
+                ParseWidgetShapeErrorKind::UnknownShape(name.to_owned()),
+                name_position,
+            ),
+        }
+    }
+
+    /// The arguments of `shape`, which takes exactly `N` of them.
+    fn arguments<const N: usize>(
+        &self,
+        shape: &'static str,
+        arguments: Vec<(usize, Argument<'a>)>,
+        name_position: usize,
+    ) -> Result<[(usize, Argument<'a>); N], ParseWidgetShapeError> {
+        arguments.try_into().or_else(|arguments: Vec<_>| {
+            self.error(
+                ParseWidgetShapeErrorKind::WrongArgumentCount {
+                    shape,
+                    expected: N,
+                    found: arguments.len(),
+                },
+                name_position,
+            )
+        })
+    }
+
+    fn shape_argument(
+        &self,
+        (position, argument): (usize, Argument<'a>),
+    ) -> Result<Box<WidgetShape>, ParseWidgetShapeError> {
+        match argument {
+            Argument::Shape(shape) => Ok(Box::new(shape)),
+            Argument::Number(_) => self.error(ParseWidgetShapeErrorKind::ExpectedShape, position),
+        }
+    }
+
+    fn number_argument(
+        &self,
+        (position, argument): (usize, Argument<'a>),
+    ) -> Result<f32, ParseWidgetShapeError> {
+        match argument {
+            Argument::Number(text) => text.parse::<f32>().or_else(|_| {
+                self.error(
+                    ParseWidgetShapeErrorKind::InvalidNumber(text.to_string()),
+                    position,
+                )
+            }),
+            Argument::Shape(_) => self.error(ParseWidgetShapeErrorKind::ExpectedNumber, position),
+        }
+    }
+
+    fn integer_argument(
+        &self,
+        (position, argument): (usize, Argument<'a>),
+    ) -> Result<usize, ParseWidgetShapeError> {
+        match argument {
+            Argument::Number(text) => text.parse::<usize>().or_else(|_| {
+                self.error(
+                    ParseWidgetShapeErrorKind::InvalidInteger(text.to_string()),
+                    position,
+                )
+            }),
+            Argument::Shape(_) => self.error(ParseWidgetShapeErrorKind::ExpectedNumber, position),
+        }
+    }
+
+    /// Whether the next word is one of the names `Display` gives to
+    /// non-finite numbers, rather than a shape.
+    fn at_non_finite_number(&self) -> bool {
+        let word = self.source[self.position..]
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .next()
+            .unwrap_or_default();
+
+        ["nan", "inf", "infinity"]
+            .iter()
+            .any(|name| word.eq_ignore_ascii_case(name))
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<(usize, Argument<'a>)>, ParseWidgetShapeError> {
+        let mut arguments = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() != Some('(') {
+            // Parameterless shapes may omit the parentheses.
+            return Ok(arguments);
+        }
+        self.expect('(')?;
+
+        self.skip_whitespace();
+        if self.peek() == Some(')') {
+            self.expect(')')?;
+            return Ok(arguments);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let argument_position = self.position;
+
+            let argument = match self.peek() {
+                Some(c) if c.is_ascii_alphabetic() && !self.at_non_finite_number() => {
+                    if self.depth == Self::MAX_DEPTH {
+                        return self.error(
+                            ParseWidgetShapeErrorKind::TooDeeplyNested,
+                            argument_position,
+                        );
+                    }
+
+                    self.depth += 1;
+                    let shape = self.parse_shape()?;
+                    self.depth -= 1;
+                    Argument::Shape(shape)
+                }
+                Some(c) if c.is_ascii_alphanumeric() || "+-.".contains(c) => {
+                    let mut prev = ' ';
+                    Argument::Number(self.take_while(|c| {
+                        // Allow signs only at the start and in exponents.
+                        // Letters are left to `f32::from_str()`, which reads
+                        // exponents, `NaN` and `inf`.
+                        let accepted = c.is_ascii_alphanumeric()
+                            || c == '.'
+                            || ("+-".contains(c) && " eE".contains(prev));
+                        prev = c;
+                        accepted
+                    }))
+                }
+                Some(c) => {
+                    return self.error(
+                        ParseWidgetShapeErrorKind::UnexpectedCharacter(c),
+                        argument_position,
+                    )
+                }
+                None => {
+                    return self.error(ParseWidgetShapeErrorKind::UnexpectedEnd, argument_position)
+                }
+            };
+
+            arguments.push((argument_position, argument));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.expect(',')?,
+                Some(')') => {
+                    self.expect(')')?;
+                    return Ok(arguments);
+                }
+                Some(c) => {
+                    return self.error(
+                        ParseWidgetShapeErrorKind::UnexpectedCharacter(c),
+                        self.position,
+                    )
+                }
+                None => return self.error(ParseWidgetShapeErrorKind::UnexpectedEnd, self.position),
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl FromStr for WidgetShape {
+    type Err = ParseWidgetShapeError;
+
+    /// Parses expressions like `min(squircle(4), rotated(polygon(6), 0.5))`,
+    /// the same format `Display` prints. Radius tables are written as
+    /// `linear_table(1, 0.9, ...)` or `cubic_table(1, 0.9, ...)`.
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Parser {
+            source,
+            position: 0,
+            depth: 0,
+        }
+        .parse_expression()
+    }
+}
+
+impl Display for WidgetShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WidgetShape::Circle => write!(f, "circle"),
+            WidgetShape::Square => write!(f, "square"),
+            WidgetShape::Squircle(factor) => write!(f, "squircle({factor})"),
+            WidgetShape::Polygon(n) => write!(f, "polygon({n})"),
+            WidgetShape::SuperPolygon(n, factor) => write!(f, "super_polygon({n}, {factor})"),
+            WidgetShape::Rotated(shape, rotation) => write!(f, "rotated({shape}, {rotation})"),
+            WidgetShape::Scaled(shape, scale) => write!(f, "scaled({shape}, {scale})"),
+            WidgetShape::Mix(shape_a, shape_b, t) => write!(f, "mix({shape_a}, {shape_b}, {t})"),
+            WidgetShape::Min(shape_a, shape_b) => write!(f, "min({shape_a}, {shape_b})"),
+            WidgetShape::Max(shape_a, shape_b) => write!(f, "max({shape_a}, {shape_b})"),
+            WidgetShape::Custom(shape) => match shape.radius_table() {
+                Some((radii, RadiusInterpolation::Linear)) => {
+                    write!(f, "linear_table({})", radii.iter().join(", "))
+                }
+                Some((radii, RadiusInterpolation::Cubic)) => {
+                    write!(f, "cubic_table({})", radii.iter().join(", "))
+                }
+                // Radius functions have no textual form and don't parse back.
+                None => write!(f, "custom"),
+            },
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> WidgetShape {
+        source.parse().unwrap()
+    }
+
+    fn parse_error(source: &str) -> (ParseWidgetShapeErrorKind, usize) {
+        let error = source.parse::<WidgetShape>().unwrap_err();
+        (error.kind, error.position)
+    }
+
+    #[test]
+    fn parses_every_shape() {
+        let table = |radii: &[f32], interpolation| {
+            WidgetShape::Custom(CustomShape::from_radius_table(radii, interpolation))
+        };
+
+        for (source, shape) in [
+            ("circle", WidgetShape::Circle),
+            ("circle()", WidgetShape::Circle),
+            ("square", WidgetShape::Square),
+            ("squircle(4)", WidgetShape::Squircle(4.0)),
+            ("polygon(6)", WidgetShape::Polygon(6)),
+            ("super_polygon(5, 1.5)", WidgetShape::SuperPolygon(5, 1.5)),
+            (
+                "rotated(square, -0.5)",
+                WidgetShape::Rotated(Box::new(WidgetShape::Square), -0.5),
+            ),
+            (
+                "scaled(circle, 1e-1)",
+                WidgetShape::Scaled(Box::new(WidgetShape::Circle), 0.1),
+            ),
+            (
+                "mix(circle, square, .25)",
+                WidgetShape::Mix(
+                    Box::new(WidgetShape::Circle),
+                    Box::new(WidgetShape::Square),
+                    0.25,
+                ),
+            ),
+            (
+                "min(circle, square)",
+                WidgetShape::Min(Box::new(WidgetShape::Circle), Box::new(WidgetShape::Square)),
+            ),
+            (
+                "max(circle, square)",
+                WidgetShape::Max(Box::new(WidgetShape::Circle), Box::new(WidgetShape::Square)),
+            ),
+            (
+                "linear_table(1, 0.5, +2)",
+                table(&[1.0, 0.5, 2.0], RadiusInterpolation::Linear),
+            ),
+            ("cubic_table(1)", table(&[1.0], RadiusInterpolation::Cubic)),
+        ] {
+            assert_eq!(parse(source), shape, "{source}");
+        }
+    }
+
+    #[test]
+    fn parses_nested_shapes_and_whitespace() {
+        let expected = WidgetShape::Min(
+            Box::new(WidgetShape::Squircle(4.0)),
+            Box::new(WidgetShape::Rotated(
+                Box::new(WidgetShape::Mix(
+                    Box::new(WidgetShape::Polygon(6)),
+                    Box::new(WidgetShape::Circle),
+                    0.5,
+                )),
+                0.5,
+            )),
+        );
+
+        assert_eq!(
+            parse("min(squircle(4),rotated(mix(polygon(6),circle,0.5),0.5))"),
+            expected
+        );
+        assert_eq!(
+            parse(" min ( squircle ( 4 ) ,\n\trotated( mix(polygon(6), circle(), 0.5) , 0.5 ) ) "),
+            expected
+        );
+    }
+
+    #[test]
+    fn reports_errors_and_their_positions() {
+        use ParseWidgetShapeErrorKind::*;
+
+        for (source, error) in [
+            ("", (UnexpectedEnd, 0)),
+            ("squircle(4", (UnexpectedEnd, 10)),
+            ("min(circle, ", (UnexpectedEnd, 12)),
+            ("squircle(4;", (UnexpectedCharacter(';'), 10)),
+            ("squircle(#)", (UnexpectedCharacter('#'), 9)),
+            ("circle square", (TrailingCharacters, 7)),
+            ("squircle(4))", (TrailingCharacters, 11)),
+            ("triangle", (UnknownShape("triangle".to_owned()), 0)),
+            ("min(circle, blob)", (UnknownShape("blob".to_owned()), 12)),
+            ("custom", (UnknownShape("custom".to_owned()), 0)),
+            ("squircle(4x)", (InvalidNumber("4x".to_owned()), 9)),
+            ("squircle(1.2.3)", (InvalidNumber("1.2.3".to_owned()), 9)),
+            ("polygon(2.5)", (InvalidInteger("2.5".to_owned()), 8)),
+            ("polygon(-3)", (InvalidInteger("-3".to_owned()), 8)),
+            ("(circle)", (ExpectedShape, 0)),
+            ("rotated(1, 2)", (ExpectedShape, 8)),
+            ("squircle(circle)", (ExpectedNumber, 9)),
+            ("linear_table()", (ExpectedNumber, 14)),
+            (
+                "circle(1)",
+                (
+                    WrongArgumentCount {
+                        shape: "circle",
+                        expected: 0,
+                        found: 1,
+                    },
+                    0,
+                ),
+            ),
+            (
+                "  mix(circle, square)",
+                (
+                    WrongArgumentCount {
+                        shape: "mix",
+                        expected: 3,
+                        found: 2,
+                    },
+                    2,
+                ),
+            ),
+        ] {
+            assert_eq!(parse_error(source), error, "{source}");
+        }
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested =
+            |depth: usize| format!("{}circle{}", "rotated(".repeat(depth), ", 1)".repeat(depth));
+
+        assert!(nested(Parser::MAX_DEPTH).parse::<WidgetShape>().is_ok());
+        assert_eq!(
+            parse_error(&nested(Parser::MAX_DEPTH + 1)),
+            (
+                ParseWidgetShapeErrorKind::TooDeeplyNested,
+                "rotated(".len() * (Parser::MAX_DEPTH + 1)
+            )
+        );
+
+        // Runaway input fails instead of overflowing the stack.
+        assert_eq!(
+            parse_error(&"min(".repeat(200_000)).0,
+            ParseWidgetShapeErrorKind::TooDeeplyNested
+        );
+    }
+
+    #[test]
+    fn round_trips_non_finite_numbers() {
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let text =
+                WidgetShape::Scaled(Box::new(WidgetShape::Squircle(value)), value).to_string();
+            assert_eq!(text.parse::<WidgetShape>().unwrap().to_string(), text);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod proptests {
+    use proptest::collection::vec;
+    use proptest::num::f32::ANY;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn widget_shape() -> impl Strategy<Value = WidgetShape> {
+        let interpolation = prop_oneof![
+            Just(RadiusInterpolation::Linear),
+            Just(RadiusInterpolation::Cubic),
+        ];
+
+        let leaf = prop_oneof![
+            Just(WidgetShape::Circle),
+            Just(WidgetShape::Square),
+            ANY.prop_map(WidgetShape::Squircle),
+            any::<usize>().prop_map(WidgetShape::Polygon),
+            (any::<usize>(), ANY).prop_map(|(n, factor)| WidgetShape::SuperPolygon(n, factor)),
+            (vec(ANY, 1..8), interpolation).prop_map(|(radii, interpolation)| {
+                WidgetShape::Custom(CustomShape::from_radius_table(radii, interpolation))
+            }),
+        ];
+
+        leaf.prop_recursive(6, 32, 3, |inner| {
+            prop_oneof![
+                (inner.clone(), ANY)
+                    .prop_map(|(shape, rotation)| WidgetShape::Rotated(Box::new(shape), rotation)),
+                (inner.clone(), ANY)
+                    .prop_map(|(shape, scale)| WidgetShape::Scaled(Box::new(shape), scale)),
+                (inner.clone(), inner.clone(), ANY).prop_map(|(shape_a, shape_b, t)| {
+                    WidgetShape::Mix(Box::new(shape_a), Box::new(shape_b), t)
+                }),
+                (inner.clone(), inner.clone()).prop_map(|(shape_a, shape_b)| {
+                    WidgetShape::Min(Box::new(shape_a), Box::new(shape_b))
+                }),
+                (inner.clone(), inner).prop_map(|(shape_a, shape_b)| {
+                    WidgetShape::Max(Box::new(shape_a), Box::new(shape_b))
+                }),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn display_parses_back(shape in widget_shape()) {
+            let text = shape.to_string();
+            let parsed = text.parse::<WidgetShape>();
+
+            prop_assert!(parsed.is_ok(), "{text}: {parsed:?}");
+            let parsed = parsed.unwrap();
+
+            // NaN arguments never compare equal, their text still has to.
+            prop_assert_eq!(parsed.to_string(), text.clone());
+            if !text.contains("NaN") {
+                prop_assert_eq!(parsed, shape);
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use crate::common::ValidationError;
+
+// ----------------------------------------------------------------------------
+
 pub struct CompassAxisLabels {
     pub(crate) inner: [String; 4],
 }
@@ -11,17 +15,27 @@ impl From<[&str; 4]> for CompassAxisLabels {
 }
 
 impl CompassAxisLabels {
+    /// Takes the first 4 labels of `source`, missing ones are left empty.
+    /// See `try_from_slice()` for a strict version.
     pub fn from_slice<T>(source: &[T]) -> CompassAxisLabels
     where
         T: ToString,
     {
         CompassAxisLabels {
-            inner: source
-                .iter()
-                .map(T::to_string)
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap(),
+            inner: std::array::from_fn(|index| {
+                source.get(index).map(T::to_string).unwrap_or_default()
+            }),
+        }
+    }
+
+    pub fn try_from_slice<T>(source: &[T]) -> Result<CompassAxisLabels, ValidationError>
+    where
+        T: ToString,
+    {
+        if source.len() == 4 {
+            Ok(Self::from_slice(source))
+        } else {
+            Err(ValidationError::AxisLabelCount(source.len()))
         }
     }
 }
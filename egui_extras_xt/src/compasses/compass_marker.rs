@@ -5,7 +5,8 @@ use egui::{vec2, Align2, Color32, FontFamily, FontId, Rect, Shape, Stroke, Strok
 use itertools::Itertools;
 use strum::Display;
 
-use crate::common::normalized_angle_unsigned_excl;
+use crate::angle::{normalized_angle_unsigned_excl, Angle};
+use crate::common::ValidationError;
 use crate::hash::PearsonHash;
 
 // ----------------------------------------------------------------------------
@@ -13,6 +14,7 @@ use crate::hash::PearsonHash;
 #[must_use]
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum DefaultCompassMarkerColor {
     #[strum(to_string = "System")]
     System,
@@ -76,6 +78,7 @@ pub struct CompassMarker<'a> {
 }
 
 impl<'a> CompassMarker<'a> {
+    /// A marker at `angle` radians, whatever the `unit` of the compass.
     pub fn new(angle: f32) -> Self {
         Self {
             angle: normalized_angle_unsigned_excl(angle),
@@ -86,6 +89,10 @@ impl<'a> CompassMarker<'a> {
         }
     }
 
+    pub fn from_angle(angle: Angle) -> Self {
+        Self::new(angle.radians())
+    }
+
     pub fn distance(mut self, distance: f32) -> Self {
         self.distance = Some(distance);
         self
@@ -96,6 +103,11 @@ impl<'a> CompassMarker<'a> {
         self
     }
 
+    pub fn try_shape(self, shape: CompassMarkerShape) -> Result<Self, ValidationError> {
+        shape.validate()?;
+        Ok(self.shape(shape))
+    }
+
     pub fn label(mut self, label: &'a str) -> Self {
         self.label = Some(label);
         self
@@ -111,6 +123,7 @@ impl<'a> CompassMarker<'a> {
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CompassMarkerShape {
     #[strum(to_string = "Square")]
     Square,
@@ -141,6 +154,17 @@ pub enum CompassMarkerShape {
 }
 
 impl CompassMarkerShape {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match *self {
+            CompassMarkerShape::Star(rays, _) if rays < 2 => Err(ValidationError::StarRays(rays)),
+            CompassMarkerShape::Star(_, ratio) if !(0.0..=1.0).contains(&ratio) => {
+                Err(ValidationError::StarRatio(ratio))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Expects a shape that passed `validate()`.
     pub(crate) fn paint(
         &self,
         ui: &mut Ui,
@@ -218,12 +242,6 @@ impl CompassMarkerShape {
                 ));
             }
             CompassMarkerShape::Star(rays, ratio) => {
-                assert!(rays >= 2, "star-shaped markers must have at least 2 rays");
-                assert!(
-                    (0.0..=1.0).contains(&ratio),
-                    "ray ratio of star-shaped markers must be normalized"
-                );
-
                 let outer_radius = rect.width() * 0.5;
                 let inner_radius = outer_radius * ratio;
                 let star_rotation = -TAU * 0.25;
@@ -262,3 +280,45 @@ impl CompassMarkerShape {
         }
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        for color in [
+            DefaultCompassMarkerColor::System,
+            DefaultCompassMarkerColor::Fixed(Color32::from_rgb(12, 34, 56)),
+            DefaultCompassMarkerColor::HsvByAngle {
+                hue_phase: 0.5,
+                saturation: 1.0,
+                value: 0.75,
+            },
+            DefaultCompassMarkerColor::HsvByLabel {
+                hue_phase: 0.25,
+                saturation: 0.5,
+                value: 1.0,
+            },
+        ] {
+            assert_serde_round_trip(color);
+        }
+
+        for shape in [
+            CompassMarkerShape::Square,
+            CompassMarkerShape::Circle,
+            CompassMarkerShape::RightArrow,
+            CompassMarkerShape::UpArrow,
+            CompassMarkerShape::LeftArrow,
+            CompassMarkerShape::DownArrow,
+            CompassMarkerShape::Diamond,
+            CompassMarkerShape::Star(5, 0.5),
+            CompassMarkerShape::Emoji('\u{1F6A9}'),
+        ] {
+            assert_serde_round_trip(shape);
+        }
+    }
+}
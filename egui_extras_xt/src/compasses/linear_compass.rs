@@ -5,10 +5,16 @@ use egui::{self, Response, Sense, StrokeKind, Ui, UiBuilder, Widget};
 use emath::{normalized_angle, pos2, vec2, Align2, Rect, Vec2};
 use epaint::{Color32, FontFamily, FontId, Stroke};
 
-use crate::common::{normalized_angle_unsigned_incl, Winding, WrapMode};
+use crate::angle::{normalized_angle_unsigned_incl, widget_values_to_radians, AngleUnit};
+use crate::common::{
+    accepts_input, angle_inline_editor, angle_slider_widget_info, animate_value, error_placeholder,
+    interact_visuals, reset_animation, validate_snap, Animation, KeyboardSteps, ScrollSteps,
+    ValidationError, Winding, WrapMode, FOCUS_RING_MARGIN,
+};
 use crate::compasses::{
     CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor,
 };
+use crate::theme::XtTheme;
 
 // ----------------------------------------------------------------------------
 
@@ -31,6 +37,7 @@ pub struct LinearCompass<'a> {
     get_set_value: GetSetValue<'a>,
     interactive: bool,
     wrap: WrapMode,
+    unit: AngleUnit,
     winding: Winding,
     width: f32,
     height: f32,
@@ -38,14 +45,17 @@ pub struct LinearCompass<'a> {
     axis_labels: CompassAxisLabels,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
     min: Option<f32>,
     max: Option<f32>,
     animated: bool,
+    animation: Option<Animation>,
     show_cursor: bool,
     show_ticks: bool,
     show_axes: bool,
     markers: &'a [CompassMarker<'a>],
-    default_marker_color: DefaultCompassMarkerColor,
+    default_marker_color: Option<DefaultCompassMarkerColor>,
     default_marker_shape: CompassMarkerShape,
 }
 
@@ -64,6 +74,7 @@ impl<'a> LinearCompass<'a> {
             get_set_value: Box::new(get_set_value),
             interactive: true,
             wrap: WrapMode::Unsigned,
+            unit: AngleUnit::Radians,
             winding: Winding::Clockwise,
             width: 512.0,
             height: 48.0,
@@ -71,18 +82,17 @@ impl<'a> LinearCompass<'a> {
             axis_labels: ["N", "E", "S", "W"].into(),
             snap: None,
             shift_snap: Some(10.0f32.to_radians()),
+            scroll_step: Some(TAU / 72.0),
+            default_value: None,
             min: None,
             max: None,
             animated: false,
+            animation: None,
             show_cursor: true,
             show_ticks: true,
             show_axes: true,
             markers: &[],
-            default_marker_color: DefaultCompassMarkerColor::HsvByAngle {
-                hue_phase: 0.0,
-                saturation: 1.0,
-                value: 1.0,
-            },
+            default_marker_color: None,
             default_marker_shape: CompassMarkerShape::Square,
         }
     }
@@ -97,6 +107,13 @@ impl<'a> LinearCompass<'a> {
         self
     }
 
+    /// Unit of the value and of the limits, snap angles, scroll step and
+    /// default value. Radians unless set.
+    pub fn unit(mut self, unit: AngleUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
     pub fn winding(mut self, winding: Winding) -> Self {
         self.winding = winding;
         self
@@ -142,11 +159,44 @@ impl<'a> LinearCompass<'a> {
         self
     }
 
+    /// Like `snap()`, but returns an error for a non-positive snap angle.
+    pub fn try_snap(self, snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(snap)?;
+        Ok(self.snap(snap))
+    }
+
+    /// Like `shift_snap()`, but returns an error for a non-positive snap angle.
+    pub fn try_shift_snap(self, shift_snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(shift_snap)?;
+        Ok(self.shift_snap(shift_snap))
+    }
+
+    /// Angle to turn by per mouse wheel notch, `None` ignores the wheel.
+    /// Ctrl (Cmd on macOS) scrolls in finer steps and Shift in coarser ones,
+    /// snaps take precedence.
+    pub fn scroll_step(mut self, scroll_step: Option<f32>) -> Self {
+        self.scroll_step = scroll_step;
+        self
+    }
+
+    /// Value to reset to on double-click.
+    pub fn default_value(mut self, default_value: Option<f32>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
     pub fn animated(mut self, animated: bool) -> Self {
         self.animated = animated;
         self
     }
 
+    /// How the displayed value follows changes when `animated`. Defaults to
+    /// linear easing over the `animation_time` of the style.
+    pub fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
     pub fn show_cursor(mut self, show_cursor: bool) -> Self {
         self.show_cursor = show_cursor;
         self
@@ -168,7 +218,7 @@ impl<'a> LinearCompass<'a> {
     }
 
     pub fn default_marker_color(mut self, default_marker_color: DefaultCompassMarkerColor) -> Self {
-        self.default_marker_color = default_marker_color;
+        self.default_marker_color = Some(default_marker_color);
         self
     }
 
@@ -176,10 +226,57 @@ impl<'a> LinearCompass<'a> {
         self.default_marker_shape = default_marker_shape;
         self
     }
+
+    pub fn try_default_marker_shape(
+        self,
+        default_marker_shape: CompassMarkerShape,
+    ) -> Result<Self, ValidationError> {
+        default_marker_shape.validate()?;
+        Ok(self.default_marker_shape(default_marker_shape))
+    }
+
+    /// Checks the configuration. An invalid configuration is shown as an
+    /// error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_snap(self.snap)?;
+        validate_snap(self.shift_snap)?;
+        self.animation
+            .as_ref()
+            .map_or(Ok(()), Animation::validate)?;
+        self.default_marker_shape.validate()?;
+        self.markers
+            .iter()
+            .filter_map(|marker| marker.shape)
+            .try_for_each(|shape| shape.validate())
+    }
 }
 
 impl<'a> Widget for LinearCompass<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        self.interactive = accepts_input(ui, self.interactive);
+
+        widget_values_to_radians(
+            self.unit,
+            &mut self.get_set_value,
+            &mut [
+                &mut self.min,
+                &mut self.max,
+                &mut self.snap,
+                &mut self.shift_snap,
+                &mut self.scroll_step,
+                &mut self.default_value,
+            ],
+        );
+
+        let theme = XtTheme::current(ui);
+        let default_marker_color = self
+            .default_marker_color
+            .unwrap_or(theme.compass_marker_color);
+
         let desired_size = egui::vec2(self.width, self.height);
 
         let (rect, mut response) = ui.allocate_exact_size(
@@ -216,6 +313,10 @@ impl<'a> Widget for LinearCompass<'a> {
             value
         };
 
+        if response.clicked() || response.drag_started() {
+            response.request_focus();
+        }
+
         if response.dragged() {
             let new_value = get(&mut self.get_set_value)
                 - response.drag_delta().x / rect.width() * (self.spread * self.winding.to_float());
@@ -225,12 +326,7 @@ impl<'a> Widget for LinearCompass<'a> {
 
         if response.drag_stopped() {
             if self.animated {
-                child_ui.ctx().clear_animations();
-                child_ui.ctx().animate_value_with_time(
-                    response.id,
-                    get(&mut self.get_set_value),
-                    0.1,
-                );
+                reset_animation(&child_ui, response.id, get(&mut self.get_set_value));
             }
 
             if let Some(snap_angle) = if child_ui.input(|input| input.modifiers.shift_only()) {
@@ -238,24 +334,97 @@ impl<'a> Widget for LinearCompass<'a> {
             } else {
                 self.snap
             } {
-                assert!(
-                    snap_angle > 0.0,
-                    "non-positive snap angles are not supported"
-                );
                 let new_value = (get(&mut self.get_set_value) / snap_angle).round() * snap_angle;
                 set(&mut self.get_set_value, constrain_value(new_value));
                 response.mark_changed();
             }
         }
 
+        let keys = KeyboardSteps::read(ui, &response);
+
+        if !keys.is_empty() {
+            // Arrow keys step by the active snap or by one degree, page keys
+            // by 15 degrees. Home and End only move to explicit limits.
+            let step = if keys.shift {
+                self.shift_snap
+            } else {
+                self.snap
+            }
+            .unwrap_or(TAU / 360.0);
+
+            let prev_value = get(&mut self.get_set_value);
+
+            let new_value = match (keys.home, keys.end) {
+                (true, _) => self.min.unwrap_or(prev_value),
+                (_, true) => self.max.unwrap_or(prev_value),
+                _ => prev_value + keys.offset(step, TAU / 24.0),
+            };
+
+            set(&mut self.get_set_value, constrain_value(new_value));
+            response.mark_changed();
+        }
+
+        if self.interactive {
+            if let Some(scroll_step) = self.scroll_step {
+                let scroll = ScrollSteps::read(ui, &response);
+
+                if !scroll.is_empty() {
+                    let new_value = scroll.apply(
+                        ui,
+                        response.id.with("scroll"),
+                        get(&mut self.get_set_value),
+                        scroll_step,
+                        self.snap,
+                        self.shift_snap,
+                    );
+
+                    set(&mut self.get_set_value, constrain_value(new_value));
+                    response.mark_changed();
+                }
+            }
+        }
+
+        if self.interactive {
+            if let Some(default_value) = self.default_value {
+                if response.double_clicked() {
+                    set(&mut self.get_set_value, default_value);
+                    response.mark_changed();
+                }
+            }
+
+            if let Some(new_value) = angle_inline_editor(
+                ui,
+                &response,
+                get(&mut self.get_set_value),
+                self.unit,
+                self.min,
+                self.max,
+            ) {
+                set(&mut self.get_set_value, constrain_value(new_value));
+                response.mark_changed();
+            }
+        }
+
+        angle_slider_widget_info(
+            ui,
+            &response,
+            get(&mut self.get_set_value),
+            self.unit,
+            self.wrap,
+            self.min,
+            self.max,
+            self.snap,
+        );
+
         if child_ui.is_rect_visible(rect) {
-            let visuals = *child_ui.style().interact(&response);
+            let visuals = interact_visuals(&child_ui, &response, self.interactive);
 
             let value = if self.animated && !response.dragged() {
-                child_ui.ctx().animate_value_with_time(
+                animate_value(
+                    &child_ui,
                     response.id,
                     get(&mut self.get_set_value),
-                    child_ui.style().animation_time,
+                    self.animation,
                 )
             } else {
                 get(&mut self.get_set_value)
@@ -275,6 +444,15 @@ impl<'a> Widget for LinearCompass<'a> {
                 StrokeKind::Middle,
             );
 
+            if response.has_focus() {
+                ui.painter().rect_stroke(
+                    rect.expand(FOCUS_RING_MARGIN),
+                    visuals.corner_radius,
+                    ui.style().visuals.selection.stroke,
+                    StrokeKind::Outside,
+                );
+            }
+
             {
                 let paint_marker = |child_ui: &mut Ui,
                                     angle: f32,
@@ -330,7 +508,7 @@ impl<'a> Widget for LinearCompass<'a> {
                     for marker in self.markers.iter() {
                         let marker_color = marker
                             .color
-                            .unwrap_or_else(|| self.default_marker_color.color(&child_ui, marker));
+                            .unwrap_or_else(|| default_marker_color.color(&child_ui, marker));
 
                         let marker_stroke = {
                             let stroke_color = tint_color_towards(
@@ -447,3 +625,30 @@ impl<'a> Widget for LinearCompass<'a> {
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_a_slider() {
+        let mut value = -30.0;
+        let node = accesskit_node(|ui| {
+            ui.add(
+                LinearCompass::new(&mut value)
+                    .unit(AngleUnit::Degrees)
+                    .wrap(WrapMode::Signed),
+            )
+        });
+
+        assert_eq!(node.role(), Role::Slider);
+        assert_eq!(node.value(), Some("-30.0\u{00B0}"));
+        assert!((node.min_numeric_value().unwrap() + 180.0).abs() < 1e-3);
+        assert!((node.max_numeric_value().unwrap() - 180.0).abs() < 1e-3);
+    }
+}
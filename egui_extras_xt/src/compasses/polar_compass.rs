@@ -9,12 +9,19 @@ use emath::normalized_angle;
 
 use strum::{Display, EnumIter};
 
+use crate::angle::{
+    snap_wrap_constrain_angle, widget_values_to_radians, wrap_constrain_angle, AngleUnit, SymLog,
+};
 use crate::common::{
-    snap_wrap_constrain_angle, Orientation, RotatedText, SymLog, Winding, WrapMode,
+    accepts_input, angle_inline_editor, angle_slider_widget_info, animate_value, error_placeholder,
+    interact_inside, interact_visuals, validate_not_negative, validate_positive, validate_snap,
+    Animation, KeyboardSteps, Orientation, RotatedText, ScrollSteps, ValidationError, WidgetShape,
+    Winding, WrapMode, FOCUS_RING_MARGIN,
 };
 use crate::compasses::{
     CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor,
 };
+use crate::theme::XtTheme;
 
 // ----------------------------------------------------------------------------
 
@@ -43,6 +50,16 @@ pub enum PolarCompassOverflow {
 
 // ----------------------------------------------------------------------------
 
+fn validate_log_base(scale_log_base: f32) -> Result<(), ValidationError> {
+    if scale_log_base > 1.0 && scale_log_base.is_finite() {
+        Ok(())
+    } else {
+        Err(ValidationError::LogBase(scale_log_base))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct PolarCompass<'a> {
     get_set_value: GetSetValue<'a>,
@@ -50,13 +67,17 @@ pub struct PolarCompass<'a> {
     orientation: Orientation,
     winding: Winding,
     overflow: PolarCompassOverflow,
-    diameter: f32,
+    diameter: Option<f32>,
     wrap: WrapMode,
+    unit: AngleUnit,
     min: Option<f32>,
     max: Option<f32>,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
     animated: bool,
+    animation: Option<Animation>,
     axis_labels: CompassAxisLabels,
     axis_label_height: f32,
     max_distance: f32,
@@ -70,11 +91,14 @@ pub struct PolarCompass<'a> {
     show_marker_labels: bool,
     show_marker_lines: bool,
     markers: &'a [CompassMarker<'a>],
-    default_marker_color: DefaultCompassMarkerColor,
+    default_marker_color: Option<DefaultCompassMarkerColor>,
     default_marker_shape: CompassMarkerShape,
 }
 
 impl<'a> PolarCompass<'a> {
+    /// Guards against huge allocations from scales with countless rings.
+    const MAX_RING_COUNT: f32 = 256.0;
+
     pub fn new(value: &'a mut f32) -> Self {
         Self::from_get_set(move |v: Option<f32>| {
             if let Some(v) = v {
@@ -91,13 +115,17 @@ impl<'a> PolarCompass<'a> {
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
             overflow: PolarCompassOverflow::Saturate,
-            diameter: 256.0,
+            diameter: None,
             wrap: WrapMode::Unsigned,
+            unit: AngleUnit::Radians,
             min: None,
             max: None,
             snap: None,
             shift_snap: Some(15.0f32.to_radians()),
+            scroll_step: Some(TAU / 72.0),
+            default_value: None,
             animated: false,
+            animation: None,
             axis_labels: ["N", "E", "S", "W"].into(),
             axis_label_height: 24.0,
             max_distance: 10000.0,
@@ -111,11 +139,7 @@ impl<'a> PolarCompass<'a> {
             show_marker_labels: true,
             show_marker_lines: true,
             markers: &[],
-            default_marker_color: DefaultCompassMarkerColor::HsvByAngle {
-                hue_phase: 0.0,
-                saturation: 1.0,
-                value: 1.0,
-            },
+            default_marker_color: None,
             default_marker_shape: CompassMarkerShape::Square,
         }
     }
@@ -141,16 +165,27 @@ impl<'a> PolarCompass<'a> {
     }
 
     pub fn diameter(mut self, diameter: f32) -> Self {
-        assert!(diameter > 0.0);
-        self.diameter = diameter;
+        self.diameter = Some(diameter);
         self
     }
 
+    pub fn try_diameter(self, diameter: f32) -> Result<Self, ValidationError> {
+        validate_positive("diameter", diameter)?;
+        Ok(self.diameter(diameter))
+    }
+
     pub fn wrap(mut self, wrap: WrapMode) -> Self {
         self.wrap = wrap;
         self
     }
 
+    /// Unit of the value and of the limits, snap angles, scroll step and
+    /// default value. Radians unless set.
+    pub fn unit(mut self, unit: AngleUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
     pub fn min(mut self, min: Option<f32>) -> Self {
         self.min = min;
         self
@@ -171,59 +206,122 @@ impl<'a> PolarCompass<'a> {
         self
     }
 
+    /// Like `snap()`, but returns an error for a non-positive snap angle.
+    pub fn try_snap(self, snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(snap)?;
+        Ok(self.snap(snap))
+    }
+
+    /// Like `shift_snap()`, but returns an error for a non-positive snap angle.
+    pub fn try_shift_snap(self, shift_snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(shift_snap)?;
+        Ok(self.shift_snap(shift_snap))
+    }
+
+    /// Angle to turn by per mouse wheel notch, `None` ignores the wheel.
+    /// Ctrl (Cmd on macOS) scrolls in finer steps and Shift in coarser ones,
+    /// snaps take precedence.
+    pub fn scroll_step(mut self, scroll_step: Option<f32>) -> Self {
+        self.scroll_step = scroll_step;
+        self
+    }
+
+    /// Value to reset to on double-click.
+    pub fn default_value(mut self, default_value: Option<f32>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
     pub fn animated(mut self, animated: bool) -> Self {
         self.animated = animated;
         self
     }
 
+    /// How the displayed value follows changes when `animated`. Defaults to
+    /// linear easing over the `animation_time` of the style.
+    pub fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
     pub fn scale_log_base(mut self, scale_log_base: f32) -> Self {
-        assert!(scale_log_base > 1.0);
         self.scale_log_base = scale_log_base;
         self
     }
 
+    pub fn try_scale_log_base(self, scale_log_base: f32) -> Result<Self, ValidationError> {
+        validate_log_base(scale_log_base)?;
+        Ok(self.scale_log_base(scale_log_base))
+    }
+
     pub fn scale_log_mult(mut self, scale_log_mult: f32) -> Self {
-        assert!(scale_log_mult > 0.0);
         self.scale_log_mult = scale_log_mult;
         self
     }
 
+    pub fn try_scale_log_mult(self, scale_log_mult: f32) -> Result<Self, ValidationError> {
+        validate_positive("scale log multiplier", scale_log_mult)?;
+        Ok(self.scale_log_mult(scale_log_mult))
+    }
+
     pub fn axis_labels(mut self, axis_labels: CompassAxisLabels) -> Self {
         self.axis_labels = axis_labels;
         self
     }
 
     pub fn axis_label_height(mut self, axis_label_height: f32) -> Self {
-        assert!(axis_label_height > 0.0);
         self.axis_label_height = axis_label_height;
         self
     }
 
+    pub fn try_axis_label_height(self, axis_label_height: f32) -> Result<Self, ValidationError> {
+        validate_positive("axis label height", axis_label_height)?;
+        Ok(self.axis_label_height(axis_label_height))
+    }
+
     pub fn max_distance(mut self, max_distance: f32) -> Self {
-        assert!(max_distance >= 0.0);
         self.max_distance = max_distance;
         self
     }
 
+    pub fn try_max_distance(self, max_distance: f32) -> Result<Self, ValidationError> {
+        validate_not_negative("max distance", max_distance)?;
+        Ok(self.max_distance(max_distance))
+    }
+
     pub fn ring_count(mut self, ring_count: usize) -> Self {
-        assert!(ring_count > 0);
         self.scale_log_base = (self.max_distance.ln() / ring_count as f32).exp();
         self.scale_log_mult = 1.0;
         self
     }
 
+    pub fn try_ring_count(self, ring_count: usize) -> Result<Self, ValidationError> {
+        validate_positive("ring count", ring_count as f32)?;
+        let compass = self.ring_count(ring_count);
+        validate_log_base(compass.scale_log_base)?;
+        Ok(compass)
+    }
+
     pub fn marker_near_size(mut self, marker_near_size: f32) -> Self {
-        assert!(marker_near_size > 0.0);
         self.marker_near_size = marker_near_size;
         self
     }
 
+    pub fn try_marker_near_size(self, marker_near_size: f32) -> Result<Self, ValidationError> {
+        validate_positive("marker near size", marker_near_size)?;
+        Ok(self.marker_near_size(marker_near_size))
+    }
+
     pub fn marker_far_size(mut self, marker_far_size: f32) -> Self {
-        assert!(marker_far_size > 0.0);
         self.marker_far_size = marker_far_size;
         self
     }
 
+    pub fn try_marker_far_size(self, marker_far_size: f32) -> Result<Self, ValidationError> {
+        validate_positive("marker far size", marker_far_size)?;
+        Ok(self.marker_far_size(marker_far_size))
+    }
+
     pub fn show_axes(mut self, show_axes: bool) -> Self {
         self.show_axes = show_axes;
         self
@@ -255,7 +353,7 @@ impl<'a> PolarCompass<'a> {
     }
 
     pub fn default_marker_color(mut self, default_marker_color: DefaultCompassMarkerColor) -> Self {
-        self.default_marker_color = default_marker_color;
+        self.default_marker_color = Some(default_marker_color);
         self
     }
 
@@ -263,16 +361,86 @@ impl<'a> PolarCompass<'a> {
         self.default_marker_shape = default_marker_shape;
         self
     }
+
+    pub fn try_default_marker_shape(
+        self,
+        default_marker_shape: CompassMarkerShape,
+    ) -> Result<Self, ValidationError> {
+        default_marker_shape.validate()?;
+        Ok(self.default_marker_shape(default_marker_shape))
+    }
+
+    /// Checks the configuration. An invalid configuration is shown as an
+    /// error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_snap(self.snap)?;
+        validate_snap(self.shift_snap)?;
+        self.animation
+            .as_ref()
+            .map_or(Ok(()), Animation::validate)?;
+
+        if let Some(diameter) = self.diameter {
+            validate_positive("diameter", diameter)?;
+        }
+
+        validate_log_base(self.scale_log_base)?;
+        validate_positive("scale log multiplier", self.scale_log_mult)?;
+        validate_positive("axis label height", self.axis_label_height)?;
+        validate_not_negative("max distance", self.max_distance)?;
+        validate_positive("marker near size", self.marker_near_size)?;
+        validate_positive("marker far size", self.marker_far_size)?;
+
+        if self.show_rings {
+            let ring_count = (self.max_distance / self.scale_log_mult).symlog(self.scale_log_base);
+            if ring_count.is_nan() || ring_count >= Self::MAX_RING_COUNT {
+                return Err(ValidationError::RingCount(ring_count));
+            }
+        }
+
+        self.default_marker_shape.validate()?;
+
+        self.markers.iter().try_for_each(|marker| {
+            if marker.distance.is_none() {
+                return Err(ValidationError::MarkerDistance);
+            }
+            marker.shape.map_or(Ok(()), |shape| shape.validate())
+        })
+    }
 }
 
 impl<'a> Widget for PolarCompass<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
-        let desired_size = Vec2::splat(self.diameter + self.axis_label_height * 2.0);
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
 
-        let (rect, mut response) = ui.allocate_exact_size(
+        self.interactive = accepts_input(ui, self.interactive);
+
+        widget_values_to_radians(
+            self.unit,
+            &mut self.get_set_value,
+            &mut [
+                &mut self.min,
+                &mut self.max,
+                &mut self.snap,
+                &mut self.shift_snap,
+                &mut self.scroll_step,
+                &mut self.default_value,
+            ],
+        );
+
+        let theme = XtTheme::current(ui);
+        let diameter = self.diameter.unwrap_or(theme.polar_compass_diameter);
+        let default_marker_color = self
+            .default_marker_color
+            .unwrap_or(theme.compass_marker_color);
+
+        let desired_size = Vec2::splat(diameter + self.axis_label_height * 2.0);
+
+        let (rect, response) = ui.allocate_exact_size(
             desired_size,
             if self.interactive {
-                Sense::click_and_drag()
+                Sense::focusable_noninteractive()
             } else {
                 Sense::hover()
             },
@@ -280,6 +448,23 @@ impl<'a> Widget for PolarCompass<'a> {
 
         let rotation_matrix = self.orientation.rot2();
 
+        let mut response = if self.interactive {
+            interact_inside(response, Sense::click_and_drag(), |pointer_pos| {
+                WidgetShape::Circle.contains(
+                    rect.center(),
+                    diameter / 2.0,
+                    rotation_matrix,
+                    pointer_pos,
+                )
+            })
+        } else {
+            response
+        };
+
+        if response.clicked() || response.drag_started() {
+            response.request_focus();
+        }
+
         if response.drag_started() {
             let value_before_drag = get(&mut self.get_set_value);
             ui.memory_mut(|memory| memory.data.insert_temp(response.id, value_before_drag));
@@ -294,10 +479,13 @@ impl<'a> Widget for PolarCompass<'a> {
                 -(rotation_matrix * (rect.center() - pos)).angle() * self.winding.to_float()
             };
 
-            let value_before_drag =
-                ui.memory_mut(|memory| memory.data.get_temp::<f32>(response.id).unwrap());
             let prev_value = get(&mut self.get_set_value);
 
+            // The drag may have started outside the compass shape, before the
+            // sense was extended, so there may not be a stored value yet.
+            let value_before_drag =
+                ui.memory_mut(|memory| *memory.data.get_temp_mut_or(response.id, prev_value));
+
             let mut new_value = normalized_angle(
                 screen_pos_to_angle(response.interact_pointer_pos().unwrap())
                     - screen_pos_to_angle(ui.input(|input| input.pointer.press_origin().unwrap()))
@@ -321,15 +509,101 @@ impl<'a> Widget for PolarCompass<'a> {
             response.mark_changed();
         }
 
+        let keys = KeyboardSteps::read(ui, &response);
+
+        if !keys.is_empty() {
+            // Arrow keys step by the active snap or by one degree, page keys
+            // by 15 degrees. Home and End only move to explicit limits.
+            let step = if keys.shift {
+                self.shift_snap
+            } else {
+                self.snap
+            }
+            .unwrap_or(TAU / 360.0);
+
+            let prev_value = get(&mut self.get_set_value);
+
+            let new_value = match (keys.home, keys.end) {
+                (true, _) => self.min.unwrap_or(prev_value),
+                (_, true) => self.max.unwrap_or(prev_value),
+                _ => prev_value + keys.offset(step, TAU / 24.0),
+            };
+
+            set(
+                &mut self.get_set_value,
+                wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+            );
+            response.mark_changed();
+        }
+
+        if self.interactive {
+            if let Some(scroll_step) = self.scroll_step {
+                let scroll = ScrollSteps::read(ui, &response);
+
+                if !scroll.is_empty() {
+                    let new_value = scroll.apply(
+                        ui,
+                        response.id.with("scroll"),
+                        get(&mut self.get_set_value),
+                        scroll_step,
+                        self.snap,
+                        self.shift_snap,
+                    );
+
+                    set(
+                        &mut self.get_set_value,
+                        wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+                    );
+                    response.mark_changed();
+                }
+            }
+        }
+
+        if self.interactive {
+            if let Some(default_value) = self.default_value {
+                if response.double_clicked() {
+                    set(&mut self.get_set_value, default_value);
+                    response.mark_changed();
+                }
+            }
+
+            if let Some(new_value) = angle_inline_editor(
+                ui,
+                &response,
+                get(&mut self.get_set_value),
+                self.unit,
+                self.min,
+                self.max,
+            ) {
+                set(
+                    &mut self.get_set_value,
+                    wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+                );
+                response.mark_changed();
+            }
+        }
+
+        angle_slider_widget_info(
+            ui,
+            &response,
+            get(&mut self.get_set_value),
+            self.unit,
+            self.wrap,
+            self.min,
+            self.max,
+            self.snap,
+        );
+
         if ui.is_rect_visible(rect) {
-            let visuals = *ui.style().interact(&response);
-            let radius = self.diameter / 2.0;
+            let visuals = interact_visuals(ui, &response, self.interactive);
+            let radius = diameter / 2.0;
 
             let value = if self.animated {
-                ui.ctx().animate_value_with_time(
+                animate_value(
+                    ui,
                     response.id,
                     get(&mut self.get_set_value),
-                    ui.style().animation_time,
+                    self.animation,
                 )
             } else {
                 get(&mut self.get_set_value)
@@ -342,11 +616,18 @@ impl<'a> Widget for PolarCompass<'a> {
                     ui.style().visuals.extreme_bg_color, // TODO: Semantically correct color
                     visuals.fg_stroke,                   // TODO: Semantically correct color
                 );
+
+                if response.has_focus() {
+                    ui.painter().circle_stroke(
+                        rect.center(),
+                        radius + FOCUS_RING_MARGIN,
+                        ui.style().visuals.selection.stroke,
+                    );
+                }
             }
 
             if self.show_rings {
                 let max_log = (self.max_distance / self.scale_log_mult).symlog(self.scale_log_base);
-                assert!(max_log < 256.0); // Prevent accidental OoM deaths during development
 
                 // No off-by-one bugs here, non-inclusive range end is used to
                 // avoid double rendering the outermost ring.
@@ -402,7 +683,7 @@ impl<'a> Widget for PolarCompass<'a> {
             }
 
             for marker in self.markers {
-                let marker_distance = marker.distance.expect("marker has no distance");
+                let marker_distance = marker.distance.unwrap_or_default();
 
                 if (marker_distance > self.max_distance)
                     && (self.overflow == PolarCompassOverflow::Clip)
@@ -412,7 +693,7 @@ impl<'a> Widget for PolarCompass<'a> {
 
                 let marker_color = marker
                     .color
-                    .unwrap_or_else(|| self.default_marker_color.color(ui, marker));
+                    .unwrap_or_else(|| default_marker_color.color(ui, marker));
 
                 let marker_stroke = {
                     let stroke_color =
@@ -469,3 +750,32 @@ impl<'a> Widget for PolarCompass<'a> {
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_a_slider() {
+        let mut value = 45.0;
+        let node = accesskit_node(|ui| {
+            ui.add(
+                PolarCompass::new(&mut value)
+                    .unit(AngleUnit::Degrees)
+                    .min(Some(-90.0))
+                    .max(Some(90.0)),
+            )
+        });
+
+        assert_eq!(node.role(), Role::Slider);
+        assert_eq!(node.value(), Some("45.0\u{00B0}"));
+        assert!((node.numeric_value().unwrap() - 45.0).abs() < 1e-3);
+        assert!((node.min_numeric_value().unwrap() + 90.0).abs() < 1e-3);
+        assert!((node.max_numeric_value().unwrap() - 90.0).abs() < 1e-3);
+    }
+}
@@ -3,7 +3,8 @@ use strum::{Display, EnumIter};
 
 // ----------------------------------------------------------------------------
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DisplayStyle {
     pub background_color: Color32,
     pub active_foreground_color: Color32,
@@ -54,6 +55,21 @@ impl DisplayStyle {
         )
     }
 
+    /// Desaturated variant used by widgets in a disabled `Ui`, which also
+    /// fades them towards the background.
+    #[must_use]
+    pub fn disabled(&self) -> DisplayStyle {
+        let stroke = |stroke: Stroke| Stroke::new(stroke.width, desaturate(stroke.color));
+
+        DisplayStyle {
+            background_color: desaturate(self.background_color),
+            active_foreground_color: desaturate(self.active_foreground_color),
+            active_foreground_stroke: stroke(self.active_foreground_stroke),
+            inactive_foreground_color: desaturate(self.inactive_foreground_color),
+            inactive_foreground_stroke: stroke(self.inactive_foreground_stroke),
+        }
+    }
+
     #[must_use]
     pub fn system_style(ui: &Ui) -> Self {
         DisplayStyle {
@@ -66,6 +82,17 @@ impl DisplayStyle {
     }
 }
 
+fn desaturate(color: Color32) -> Color32 {
+    let rgba = Rgba::from(color);
+    let intensity = rgba.intensity();
+    Color32::from(Rgba::from_rgba_premultiplied(
+        intensity,
+        intensity,
+        intensity,
+        rgba.a(),
+    ))
+}
+
 impl Default for DisplayStyle {
     fn default() -> Self {
         DisplayStylePreset::Default.style()
@@ -76,6 +103,7 @@ impl Default for DisplayStyle {
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum DisplayStylePreset {
     #[strum(to_string = "Default")]
     Default,
@@ -205,3 +233,21 @@ impl DisplayStylePreset {
         }
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        DisplayStylePreset::iter().for_each(assert_serde_round_trip);
+        DisplayStylePreset::iter()
+            .map(|preset| preset.style())
+            .for_each(assert_serde_round_trip);
+    }
+}
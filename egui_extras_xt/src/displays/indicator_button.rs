@@ -1,9 +1,12 @@
 use egui::{
     vec2, Align2, FontFamily, FontId, Key, Rect, Response, Sense, Stroke, StrokeKind, Ui, Widget,
+    WidgetInfo, WidgetType,
 };
 use strum::{Display, EnumIter};
 
+use crate::common::{accepts_input, interact_visuals};
 use crate::displays::{DisplayStyle, DisplayStylePreset};
+use crate::theme::XtTheme;
 
 // ----------------------------------------------------------------------------
 
@@ -22,7 +25,8 @@ fn set(get_set_value: &mut GetSetValue<'_>, value: bool) {
 // ----------------------------------------------------------------------------
 
 #[non_exhaustive]
-#[derive(Clone, Copy, Display, EnumIter, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum IndicatorButtonBehavior {
     #[strum(to_string = "Toggle")]
     Toggle,
@@ -39,7 +43,7 @@ pub struct IndicatorButton<'a> {
     width: f32,
     height: f32,
     label: Option<String>,
-    style: DisplayStyle,
+    style: Option<DisplayStyle>,
     animated: bool,
     interactive: bool,
     margin: f32,
@@ -70,7 +74,7 @@ impl<'a> IndicatorButton<'a> {
             width: 64.0,
             height: 40.0,
             label: None,
-            style: DisplayStylePreset::Default.style(),
+            style: None,
             animated: true,
             interactive: true,
             margin: 0.2,
@@ -94,12 +98,12 @@ impl<'a> IndicatorButton<'a> {
     }
 
     pub fn style(mut self, style: DisplayStyle) -> Self {
-        self.style = style;
+        self.style = Some(style);
         self
     }
 
     pub fn style_preset(mut self, preset: DisplayStylePreset) -> Self {
-        self.style = preset.style();
+        self.style = Some(preset.style());
         self
     }
 
@@ -126,6 +130,16 @@ impl<'a> IndicatorButton<'a> {
 
 impl<'a> Widget for IndicatorButton<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
+        self.interactive = accepts_input(ui, self.interactive);
+
+        let theme = XtTheme::current(ui);
+        let style = self.style.unwrap_or(theme.display_style);
+        let style = if ui.is_enabled() {
+            style
+        } else {
+            style.disabled()
+        };
+
         let desired_size = vec2(self.width, self.height);
 
         let (rect, mut response) = ui.allocate_exact_size(
@@ -170,8 +184,15 @@ impl<'a> Widget for IndicatorButton<'a> {
             }
         }
 
+        let enabled = ui.is_enabled();
+        let selected = get(&mut self.get_set_value);
+        let label = self.label.clone().unwrap_or_default();
+        response.widget_info(|| {
+            WidgetInfo::selected(WidgetType::SelectableLabel, enabled, selected, &label)
+        });
+
         if ui.is_rect_visible(rect) {
-            let visuals = *ui.style().interact(&response);
+            let visuals = interact_visuals(ui, &response, self.interactive);
 
             let value = if self.animated {
                 ui.ctx()
@@ -204,7 +225,7 @@ impl<'a> Widget for IndicatorButton<'a> {
                 ui.painter().rect(
                     indicator_rect.shrink(margin),
                     4.0,
-                    self.style.background_color,
+                    style.background_color,
                     Stroke::NONE,
                     StrokeKind::Middle,
                 );
@@ -212,7 +233,7 @@ impl<'a> Widget for IndicatorButton<'a> {
                 ui.painter().rect(
                     indicator_rect.shrink(margin + 2.0),
                     4.0,
-                    self.style.foreground_color_blend(value),
+                    style.foreground_color_blend(value),
                     Stroke::NONE,
                     StrokeKind::Middle,
                 );
@@ -232,3 +253,38 @@ impl<'a> Widget for IndicatorButton<'a> {
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        IndicatorButtonBehavior::iter().for_each(assert_serde_round_trip);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::{Role, Toggled};
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_a_toggle() {
+        let mut value = true;
+        let node = accesskit_node(|ui| ui.add(IndicatorButton::new(&mut value).label("Mute")));
+
+        assert_eq!(node.role(), Role::Button);
+        assert_eq!(node.label(), Some("Mute"));
+        assert_eq!(node.toggled(), Some(Toggled::True));
+    }
+}
@@ -1,30 +1,36 @@
 use std::ops::RangeInclusive;
 
-use egui::{self, remap_clamp, Response, Sense, StrokeKind, Ui, Widget};
+use egui::{self, remap_clamp, Response, Sense, StrokeKind, Ui, Widget, WidgetInfo, WidgetType};
 use emath::Vec2;
 use epaint::Stroke;
 
+use crate::common::{
+    accesskit_numeric_range, animate_value, error_placeholder, Animation, ValidationError,
+};
 use crate::displays::{DisplayStyle, DisplayStylePreset};
+use crate::theme::XtTheme;
 
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct LedDisplay {
     value: f32,
-    diameter: f32,
+    diameter: Option<f32>,
     padding: f32,
     range: RangeInclusive<f32>,
-    style: DisplayStyle,
+    style: Option<DisplayStyle>,
     animated: bool,
+    animation: Option<Animation>,
 }
 
 impl LedDisplay {
     pub fn new(value: f32) -> Self {
         Self {
             value,
-            diameter: 16.0,
+            diameter: None,
             padding: 0.25,
             range: 0.0..=1.0,
-            style: DisplayStylePreset::Default.style(),
+            style: None,
             animated: true,
+            animation: None,
         }
     }
 
@@ -33,7 +39,7 @@ impl LedDisplay {
     }
 
     pub fn diameter(mut self, diameter: impl Into<f32>) -> Self {
-        self.diameter = diameter.into();
+        self.diameter = Some(diameter.into());
         self
     }
 
@@ -48,12 +54,12 @@ impl LedDisplay {
     }
 
     pub fn style(mut self, style: DisplayStyle) -> Self {
-        self.style = style;
+        self.style = Some(style);
         self
     }
 
     pub fn style_preset(mut self, preset: DisplayStylePreset) -> Self {
-        self.style = preset.style();
+        self.style = Some(preset.style());
         self
     }
 
@@ -61,19 +67,52 @@ impl LedDisplay {
         self.animated = animated;
         self
     }
+
+    /// How the displayed value follows changes when `animated`. Defaults to
+    /// linear easing over the `animation_time` of the style.
+    pub fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// Checks the configuration. An invalid configuration is shown as an
+    /// error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.animation.as_ref().map_or(Ok(()), Animation::validate)
+    }
 }
 
 impl Widget for LedDisplay {
     fn ui(self, ui: &mut Ui) -> Response {
-        let desired_size = Vec2::splat(self.diameter + self.padding * self.diameter);
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        let theme = XtTheme::current(ui);
+        let diameter = self.diameter.unwrap_or(theme.led_diameter);
+        let style = self.style.unwrap_or(theme.display_style);
+        let style = if ui.is_enabled() {
+            style
+        } else {
+            style.disabled()
+        };
+
+        let desired_size = Vec2::splat(diameter + self.padding * diameter);
 
         let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
 
+        let enabled = ui.is_enabled();
+        response.widget_info(|| WidgetInfo {
+            enabled,
+            value: Some(f64::from(self.value)),
+            ..WidgetInfo::new(WidgetType::ProgressIndicator)
+        });
+        accesskit_numeric_range(&response, Some(self.range.clone()), None);
+
         if ui.is_rect_visible(rect) {
             let value = remap_clamp(
                 if self.animated {
-                    ui.ctx()
-                        .animate_value_with_time(response.id, self.value, 0.1)
+                    animate_value(ui, response.id, self.value, self.animation)
                 } else {
                     self.value
                 },
@@ -84,19 +123,39 @@ impl Widget for LedDisplay {
             ui.painter().rect(
                 rect,
                 ui.style().visuals.noninteractive().corner_radius,
-                self.style.background_color,
+                style.background_color,
                 Stroke::NONE,
                 StrokeKind::Middle,
             );
 
             ui.painter().circle(
                 rect.center(),
-                self.diameter / 2.0,
-                self.style.foreground_color_blend(value),
-                self.style.foreground_stroke_blend(value),
+                diameter / 2.0,
+                style.foreground_color_blend(value),
+                style.foreground_stroke_blend(value),
             );
         }
 
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_a_progress_indicator() {
+        let node = accesskit_node(|ui| ui.add(LedDisplay::new(0.25).range(0.0..=0.5)));
+
+        assert_eq!(node.role(), Role::ProgressIndicator);
+        assert_eq!(node.numeric_value(), Some(0.25));
+        assert_eq!(node.min_numeric_value(), Some(0.0));
+        assert_eq!(node.max_numeric_value(), Some(0.5));
+    }
+}
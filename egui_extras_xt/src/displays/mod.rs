@@ -1,12 +1,18 @@
 mod display_style;
 mod indicator_button;
 mod led_display;
+mod sample_source;
 mod waveform_display;
+mod waveform_peaks;
 
 pub mod segmented_display;
 
 pub use display_style::{DisplayStyle, DisplayStylePreset};
 pub use indicator_button::{IndicatorButton, IndicatorButtonBehavior};
 pub use led_display::LedDisplay;
+pub use sample_source::{RingBufferSource, SampleSource, SliceSource};
 pub use segmented_display::{DisplayKind, DisplayMetrics, SegmentedDisplayWidget};
-pub use waveform_display::{BufferLayout, SignalEdge, WaveformDisplayWidget};
+pub use waveform_display::{
+    BufferLayout, ChannelLayout, SignalEdge, VerticalUnit, WaveformDisplayWidget,
+};
+pub use waveform_peaks::WaveformPeaks;
@@ -0,0 +1,236 @@
+use crate::displays::BufferLayout;
+
+// ----------------------------------------------------------------------------
+
+/// Samples read in place by `WaveformDisplayWidget`, so that buffers which
+/// aren't one contiguous slice don't have to be copied into one every frame.
+///
+/// Indices count samples the way a contiguous buffer with the same channel
+/// count and layout would.
+pub trait SampleSource<SampleType> {
+    /// Number of samples, across all channels.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn channels(&self) -> usize;
+
+    fn buffer_layout(&self) -> BufferLayout;
+
+    fn sample(&self, index: usize) -> SampleType;
+
+    /// Counter that changes whenever the samples do, e.g. the number of
+    /// samples ever written to a ring buffer. Timelines rebuild their peaks
+    /// of sources without one every frame.
+    fn generation(&self) -> Option<u64> {
+        None
+    }
+
+    fn channel_length(&self) -> usize {
+        self.len() / self.channels().max(1)
+    }
+
+    /// Sample `index` of channel `channel_id`.
+    fn channel_sample(&self, channel_id: usize, index: usize) -> SampleType {
+        match self.buffer_layout() {
+            BufferLayout::Planar => self.sample(channel_id * self.channel_length() + index),
+            BufferLayout::Interleaved => self.sample(index * self.channels() + channel_id),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Samples of all channels in one slice.
+#[derive(Clone, Copy, Debug)]
+pub struct SliceSource<'a, SampleType> {
+    samples: &'a [SampleType],
+    channels: usize,
+    buffer_layout: BufferLayout,
+}
+
+impl<'a, SampleType> SliceSource<'a, SampleType> {
+    pub fn new(samples: &'a [SampleType], channels: usize, buffer_layout: BufferLayout) -> Self {
+        Self {
+            samples,
+            channels,
+            buffer_layout,
+        }
+    }
+}
+
+impl<SampleType: Copy> SampleSource<SampleType> for SliceSource<'_, SampleType> {
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn channels(&self) -> usize {
+        self.channels
+    }
+
+    fn buffer_layout(&self) -> BufferLayout {
+        self.buffer_layout
+    }
+
+    fn sample(&self, index: usize) -> SampleType {
+        self.samples[index]
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Interleaved samples of a ring buffer that wraps around, as the two slices
+/// before and after the wrap, oldest first. Most ring buffers hand these out
+/// through an `as_slices()` method.
+#[derive(Clone, Copy, Debug)]
+pub struct RingBufferSource<'a, SampleType> {
+    head: &'a [SampleType],
+    tail: &'a [SampleType],
+    channels: usize,
+    generation: Option<u64>,
+}
+
+impl<'a, SampleType> RingBufferSource<'a, SampleType> {
+    pub fn new(head: &'a [SampleType], tail: &'a [SampleType], channels: usize) -> Self {
+        Self {
+            head,
+            tail,
+            channels,
+            generation: None,
+        }
+    }
+
+    /// Total number of samples ever written to the ring buffer, reported as
+    /// the generation of the source so that timelines only rebuild their
+    /// peaks when something was written.
+    pub fn with_generation(mut self, samples_written: u64) -> Self {
+        self.generation = Some(samples_written);
+        self
+    }
+}
+
+impl<SampleType: Copy> SampleSource<SampleType> for RingBufferSource<'_, SampleType> {
+    fn len(&self) -> usize {
+        self.head.len() + self.tail.len()
+    }
+
+    fn channels(&self) -> usize {
+        self.channels
+    }
+
+    fn buffer_layout(&self) -> BufferLayout {
+        BufferLayout::Interleaved
+    }
+
+    fn sample(&self, index: usize) -> SampleType {
+        match index.checked_sub(self.head.len()) {
+            Some(tail_index) => self.tail[tail_index],
+            None => self.head[index],
+        }
+    }
+
+    fn generation(&self) -> Option<u64> {
+        self.generation
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// One slice per channel. Channels are cut to the shortest one.
+impl<SampleType: Copy> SampleSource<SampleType> for &[&[SampleType]] {
+    fn len(&self) -> usize {
+        self.channel_length() * self.channels()
+    }
+
+    fn channels(&self) -> usize {
+        <[_]>::len(self)
+    }
+
+    fn buffer_layout(&self) -> BufferLayout {
+        BufferLayout::Planar
+    }
+
+    fn sample(&self, index: usize) -> SampleType {
+        let channel_length = self.channel_length();
+        self[index / channel_length][index % channel_length]
+    }
+
+    fn channel_length(&self) -> usize {
+        self.iter().map(|channel| channel.len()).min().unwrap_or(0)
+    }
+
+    fn channel_sample(&self, channel_id: usize, index: usize) -> SampleType {
+        self[channel_id][index]
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel<SampleType: Copy>(
+        source: &impl SampleSource<SampleType>,
+        channel_id: usize,
+    ) -> Vec<SampleType> {
+        (0..source.channel_length())
+            .map(|index| source.channel_sample(channel_id, index))
+            .collect()
+    }
+
+    #[test]
+    fn ring_buffer_reads_across_the_wrap() {
+        // Interleaved stereo, wrapped in the middle of the third frame
+        let head = [0, 10, 1, 11, 2];
+        let tail = [12, 3, 13];
+        let source = RingBufferSource::new(&head, &tail, 2);
+
+        assert_eq!(source.len(), 8);
+        assert_eq!(source.channel_length(), 4);
+        assert_eq!(
+            (0..source.len())
+                .map(|index| source.sample(index))
+                .collect::<Vec<_>>(),
+            [0, 10, 1, 11, 2, 12, 3, 13]
+        );
+        assert_eq!(channel(&source, 0), [0, 1, 2, 3]);
+        assert_eq!(channel(&source, 1), [10, 11, 12, 13]);
+
+        let empty_tail = RingBufferSource::new(&head[..4], &[], 2);
+        assert_eq!(channel(&empty_tail, 1), [10, 11]);
+
+        let empty_head = RingBufferSource::new(&[], &head[..4], 2);
+        assert_eq!(channel(&empty_head, 0), [0, 1]);
+    }
+
+    #[test]
+    fn ring_buffer_reports_its_generation() {
+        let source = RingBufferSource::new(&[0.0_f32], &[], 1);
+        assert_eq!(source.generation(), None);
+        assert_eq!(source.with_generation(42).generation(), Some(42));
+    }
+
+    #[test]
+    fn channel_slices_are_cut_to_the_shortest() {
+        let left = [0, 1, 2, 3];
+        let right = [10, 11];
+        let source: &[&[i32]] = &[&left, &right];
+
+        // Slices have inherent `len()` and `is_empty()` of their own
+        assert_eq!(source.channels(), 2);
+        assert_eq!(source.channel_length(), 2);
+        assert_eq!(SampleSource::len(&source), 4);
+        assert_eq!(channel(&source, 0), [0, 1]);
+        assert_eq!(channel(&source, 1), [10, 11]);
+        assert_eq!(
+            (0..4).map(|index| source.sample(index)).collect::<Vec<_>>(),
+            [0, 1, 10, 11]
+        );
+
+        let no_channels: &[&[i32]] = &[];
+        assert!(SampleSource::is_empty(&no_channels));
+    }
+}
@@ -1,6 +1,7 @@
 use strum::{Display, EnumIter};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DisplayMetrics {
     pub segment_spacing: f32,
     pub segment_thickness: f32,
@@ -25,7 +26,8 @@ impl Default for DisplayMetrics {
 // ----------------------------------------------------------------------------
 
 #[non_exhaustive]
-#[derive(Clone, Copy, Display, EnumIter, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum DisplayMetricsPreset {
     #[strum(to_string = "Default")]
     Default,
@@ -77,3 +79,21 @@ impl DisplayMetricsPreset {
         }
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        DisplayMetricsPreset::iter().for_each(assert_serde_round_trip);
+        DisplayMetricsPreset::iter()
+            .map(|preset| preset.metrics())
+            .for_each(assert_serde_round_trip);
+    }
+}
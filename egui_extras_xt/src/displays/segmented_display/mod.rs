@@ -16,7 +16,8 @@ use egui::Pos2;
 
 pub type DisplayGlyph = u16;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DisplayDigit {
     pub glyph: DisplayGlyph,
     pub dot: bool,
@@ -28,6 +29,7 @@ pub struct DisplayDigit {
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum DisplayKind {
     #[strum(to_string = "7-segment")]
     SevenSegment,
@@ -71,3 +73,25 @@ pub(crate) trait DisplayImpl {
         digit_median: f32,
     ) -> Vec<Vec<Pos2>>;
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        DisplayKind::iter().for_each(assert_serde_round_trip);
+
+        assert_serde_round_trip(DisplayDigit {
+            glyph: 0b0110_1101,
+            dot: true,
+            colon: false,
+            apostrophe: true,
+        });
+    }
+}
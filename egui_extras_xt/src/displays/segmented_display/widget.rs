@@ -1,18 +1,23 @@
-use egui::{pos2, vec2, Pos2, Response, Sense, Shape, Stroke, StrokeKind, Ui, UiBuilder, Widget};
+use egui::{
+    pos2, vec2, Pos2, Response, Sense, Shape, Stroke, StrokeKind, Ui, UiBuilder, Widget,
+    WidgetInfo, WidgetType,
+};
 use itertools::Itertools;
 
 use crate::displays::segmented_display::{
     DisplayDigit, DisplayKind, DisplayMetrics, DisplayMetricsPreset,
 };
 use crate::displays::{DisplayStyle, DisplayStylePreset};
+use crate::theme::XtTheme;
 
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct SegmentedDisplayWidget {
     display_kind: DisplayKind,
     digits: Vec<DisplayDigit>,
+    text: String,
     digit_height: f32,
-    metrics: DisplayMetrics,
-    style: DisplayStyle,
+    metrics: Option<DisplayMetrics>,
+    style: Option<DisplayStyle>,
     show_dots: bool,
     show_colons: bool,
     show_apostrophes: bool,
@@ -23,9 +28,10 @@ impl SegmentedDisplayWidget {
         Self {
             display_kind,
             digits: Vec::new(),
+            text: String::new(),
             digit_height: 80.0,
-            metrics: DisplayMetrics::default(),
-            style: DisplayStylePreset::Default.style(),
+            metrics: None,
+            style: None,
             show_dots: true,
             show_colons: true,
             show_apostrophes: true,
@@ -47,6 +53,7 @@ impl SegmentedDisplayWidget {
     pub fn push_string<T: AsRef<str>>(mut self, value: T) -> Self {
         let display_impl = self.display_kind.display_impl();
 
+        self.text.push_str(value.as_ref());
         self.digits.extend(
             [None]
                 .into_iter()
@@ -80,22 +87,22 @@ impl SegmentedDisplayWidget {
     }
 
     pub fn style(mut self, style: DisplayStyle) -> Self {
-        self.style = style;
+        self.style = Some(style);
         self
     }
 
     pub fn style_preset(mut self, preset: DisplayStylePreset) -> Self {
-        self.style = preset.style();
+        self.style = Some(preset.style());
         self
     }
 
     pub fn metrics(mut self, metrics: DisplayMetrics) -> Self {
-        self.metrics = metrics;
+        self.metrics = Some(metrics);
         self
     }
 
     pub fn metrics_preset(mut self, preset: DisplayMetricsPreset) -> Self {
-        self.metrics = preset.metrics();
+        self.metrics = Some(preset.metrics());
         self
     }
 
@@ -117,20 +124,31 @@ impl SegmentedDisplayWidget {
 
 impl Widget for SegmentedDisplayWidget {
     fn ui(self, ui: &mut Ui) -> Response {
+        let theme = XtTheme::current(ui);
+        let metrics = self
+            .metrics
+            .unwrap_or(theme.display_metrics_preset.metrics());
+        let style = self.style.unwrap_or(theme.display_style);
+        let style = if ui.is_enabled() {
+            style
+        } else {
+            style.disabled()
+        };
+
         let display_impl = self.display_kind.display_impl();
 
         let digit_height = self.digit_height;
-        let digit_width = digit_height * self.metrics.digit_ratio;
+        let digit_width = digit_height * metrics.digit_ratio;
 
         // Turn relative metrics to absolute metrics
-        let segment_thickness = self.metrics.segment_thickness * digit_height;
-        let segment_spacing = self.metrics.segment_spacing * digit_height;
-        let digit_shearing = self.metrics.digit_shearing * digit_width;
-        let digit_spacing = self.metrics.digit_spacing * digit_width;
-        let margin_horizontal = self.metrics.margin_horizontal * digit_width;
-        let margin_vertical = self.metrics.margin_vertical * digit_height;
-        let digit_median = self.metrics.digit_median * (digit_height / 2.0);
-        let colon_separation = self.metrics.colon_separation * (digit_height / 2.0);
+        let segment_thickness = metrics.segment_thickness * digit_height;
+        let segment_spacing = metrics.segment_spacing * digit_height;
+        let digit_shearing = metrics.digit_shearing * digit_width;
+        let digit_spacing = metrics.digit_spacing * digit_width;
+        let margin_horizontal = metrics.margin_horizontal * digit_width;
+        let margin_vertical = metrics.margin_vertical * digit_height;
+        let digit_median = metrics.digit_median * (digit_height / 2.0);
+        let colon_separation = metrics.colon_separation * (digit_height / 2.0);
 
         let desired_size = vec2(
             (digit_width * self.digits.len() as f32)
@@ -141,6 +159,8 @@ impl Widget for SegmentedDisplayWidget {
         );
 
         let (rect, response) = ui.allocate_exact_size(desired_size, Sense::click());
+        response
+            .widget_info(|| WidgetInfo::labeled(WidgetType::Label, ui.is_enabled(), &self.text));
 
         let mut child_ui = ui.new_child(UiBuilder::new().max_rect(rect).layout(*ui.layout()));
         child_ui.set_clip_rect(child_ui.clip_rect().intersect(rect));
@@ -150,7 +170,7 @@ impl Widget for SegmentedDisplayWidget {
             ui.painter().rect(
                 rect,
                 ui.style().visuals.noninteractive().corner_radius,
-                self.style.background_color,
+                style.background_color,
                 Stroke::NONE,
                 StrokeKind::Middle,
             );
@@ -191,8 +211,8 @@ impl Widget for SegmentedDisplayWidget {
                     // https://github.com/emilk/egui/issues/513
                     child_ui.painter().add(Shape::convex_polygon(
                         segment_points.iter().map(transform).collect_vec(),
-                        self.style.foreground_color(segment_active),
-                        self.style.foreground_stroke(segment_active),
+                        style.foreground_color(segment_active),
+                        style.foreground_stroke(segment_active),
                     ));
                 }
 
@@ -200,8 +220,8 @@ impl Widget for SegmentedDisplayWidget {
                     child_ui.painter().circle(
                         transform(&dot_pos),
                         segment_thickness / 2.0,
-                        self.style.foreground_color(digit.dot),
-                        self.style.foreground_stroke(digit.dot),
+                        style.foreground_color(digit.dot),
+                        style.foreground_stroke(digit.dot),
                     );
                 }
 
@@ -209,23 +229,23 @@ impl Widget for SegmentedDisplayWidget {
                     child_ui.painter().circle(
                         transform(&colon_top_pos),
                         segment_thickness / 2.0,
-                        self.style.foreground_color(digit.colon),
-                        self.style.foreground_stroke(digit.colon),
+                        style.foreground_color(digit.colon),
+                        style.foreground_stroke(digit.colon),
                     );
 
                     child_ui.painter().circle(
                         transform(&colon_bottom_pos),
                         segment_thickness / 2.0,
-                        self.style.foreground_color(digit.colon),
-                        self.style.foreground_stroke(digit.colon),
+                        style.foreground_color(digit.colon),
+                        style.foreground_stroke(digit.colon),
                     );
                 }
 
                 if self.show_apostrophes {
                     child_ui.painter().add(Shape::convex_polygon(
                         apostrophe_points.iter().map(transform).collect_vec(),
-                        self.style.foreground_color(digit.apostrophe),
-                        self.style.foreground_stroke(digit.apostrophe),
+                        style.foreground_color(digit.apostrophe),
+                        style.foreground_stroke(digit.apostrophe),
                     ));
                 }
             };
@@ -247,3 +267,21 @@ impl Widget for SegmentedDisplayWidget {
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_its_text() {
+        let node = accesskit_node(|ui| ui.add(SegmentedDisplayWidget::seven_segment("12:34")));
+
+        assert_eq!(node.role(), Role::Label);
+        assert_eq!(node.value(), Some("12:34"));
+    }
+}
@@ -1,10 +1,19 @@
-use std::ops::RangeInclusive;
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::ops::{Range, RangeInclusive};
+use std::sync::Arc;
 
+use ecolor::Hsva;
 use egui::{
-    pos2, remap_clamp, vec2, Align2, FontSelection, Rect, Response, Sense, Shape, StrokeKind, Ui,
-    Widget,
+    pos2, remap, remap_clamp, vec2, Align2, Color32, FontSelection, Id, Mesh, Painter, Rect,
+    Response, Sense, Shape, Stroke, StrokeKind, TextStyle, Ui, Vec2, Widget,
 };
 use itertools::Itertools;
+use strum::{Display, EnumIter};
+
+use crate::common::{
+    error_placeholder, interact_visuals, validate_not_negative, validate_positive, ValidationError,
+};
+use crate::displays::{SampleSource, SliceSource, WaveformPeaks};
 
 // ----------------------------------------------------------------------------
 
@@ -23,27 +32,135 @@ fn set(get_set_value: &mut GetSetValue<'_>, track_enabled: bool) {
 // ----------------------------------------------------------------------------
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum SignalEdge {
     FallingEdge,
     RisingEdge,
 }
 
-impl SignalEdge {
-    fn from_samples<SampleType>(sample_a: &SampleType, sample_b: &SampleType) -> Option<SignalEdge>
-    where
-        SampleType: SampleRange<SampleType> + PartialOrd,
-    {
-        match (*sample_a >= SampleType::ZERO, *sample_b >= SampleType::ZERO) {
-            (false, true) => Some(SignalEdge::RisingEdge),
-            (true, false) => Some(SignalEdge::FallingEdge),
-            _ => None,
+// ----------------------------------------------------------------------------
+
+/// How the channels of a multi-channel buffer are arranged in the widget.
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ChannelLayout {
+    #[strum(to_string = "Side by side")]
+    SideBySide,
+
+    #[strum(to_string = "Stacked")]
+    Stacked,
+
+    /// All channels in one lane, in distinct colors.
+    #[strum(to_string = "Overlaid")]
+    Overlaid,
+
+    /// Channel pairs plotted against each other, as on a Lissajous display
+    /// or a stereo goniometer.
+    #[strum(to_string = "XY")]
+    Xy,
+}
+
+// ----------------------------------------------------------------------------
+
+/// What the vertical axis labels of the graticule are in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum VerticalUnit {
+    /// Fractions of the display range of the sample type, from -1 to 1.
+    FullScale,
+
+    /// Volts, for a display range spanning `-full_scale..=full_scale` volts.
+    Volts { full_scale: f32 },
+
+    /// Decibels relative to full scale.
+    DecibelsFullScale,
+}
+
+impl VerticalUnit {
+    /// Formats a value in `-1.0..=1.0` full scale, with enough decimals to
+    /// tell apart values `step` apart.
+    fn format(&self, value: f32, step: f32) -> String {
+        let value = snap_to_zero(f64::from(value), f64::from(step)) as f32;
+
+        match *self {
+            VerticalUnit::FullScale => format_step(f64::from(value), f64::from(step)),
+            VerticalUnit::Volts { full_scale } => format_si(
+                f64::from(value * full_scale),
+                f64::from(step * full_scale),
+                "V",
+            ),
+            VerticalUnit::DecibelsFullScale if value == 0.0 => "-\u{221E} dB".to_owned(),
+            VerticalUnit::DecibelsFullScale => format!("{:.1} dB", 20.0 * value.abs().log10()),
         }
     }
 }
 
+/// Rounding errors shouldn't turn zero into "-140 dB".
+fn snap_to_zero(value: f64, step: f64) -> f64 {
+    if value.abs() < step.abs() / 1000.0 {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Formats `value` with one more significant digit than `step`, the
+/// difference between neighbouring values, has.
+fn format_step(value: f64, step: f64) -> String {
+    let decimals = (1.0 - step.abs().log10().floor()).clamp(0.0, 9.0) as usize;
+    let text = format!("{value:.decimals$}");
+
+    // Values that round to zero shouldn't print as "-0.0"
+    match text.strip_prefix('-') {
+        Some(unsigned) if unsigned.chars().all(|c| c == '0' || c == '.') => unsigned.to_owned(),
+        _ => text,
+    }
+}
+
+/// Formats `value` with the SI prefix suiting `step`, the difference between
+/// neighbouring values.
+fn format_si(value: f64, step: f64, unit: &str) -> String {
+    let (scale, prefix) = [(1.0, ""), (1e-3, "m"), (1e-6, "\u{B5}"), (1e-9, "n")]
+        .into_iter()
+        .find(|&(scale, _)| step.abs() >= scale)
+        .unwrap_or((1e-9, "n"));
+
+    format!(
+        "{} {prefix}{unit}",
+        format_step(value / scale, step / scale)
+    )
+}
+
+/// Vertical gain and offset of the waveform, mapping samples to `-1.0..=1.0`
+/// at the edges of the display.
+#[derive(Clone, Copy, Debug)]
+struct VerticalScale {
+    gain: f32,
+    offset: f32,
+}
+
+impl VerticalScale {
+    fn apply<SampleType: SampleRange<SampleType>>(&self, sample: f32) -> f32 {
+        remap(sample, SampleType::DISPLAY_RANGE, -1.0..=1.0) * self.gain + self.offset
+    }
+
+    fn sample_to_y<SampleType: SampleRange<SampleType>>(&self, sample: f32, rect: Rect) -> f32 {
+        remap_clamp(
+            self.apply::<SampleType>(sample),
+            -1.0..=1.0,
+            rect.bottom_up_range(),
+        )
+    }
+
+    /// Inverse of `apply()`, in full scale rather than sample units.
+    fn unapply(&self, value: f32) -> f32 {
+        (value - self.offset) / self.gain
+    }
+}
+
 // ----------------------------------------------------------------------------
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BufferLayout {
     Planar,
     Interleaved,
@@ -98,13 +215,169 @@ impl SampleRange<f64> for f64 {
 
 // ----------------------------------------------------------------------------
 
+/// Visible part of a timeline, kept in temp memory.
+#[derive(Clone, Copy, Debug)]
+struct TimelineView {
+    start: f64,
+    samples_per_point: f64,
+}
+
+impl TimelineView {
+    /// Zooming in stops at 64 points per sample.
+    const MIN_SAMPLES_PER_POINT: f64 = 1.0 / 64.0;
+
+    /// Zoom factor per point of mouse wheel scrolling.
+    const WHEEL_ZOOM_SPEED: f32 = 1.0 / 200.0;
+
+    fn update(ui: &Ui, response: &Response, channel_length: usize, width: f32) -> Self {
+        let id = response.id.with("timeline_view");
+        let width = f64::from(width);
+        let fit_samples_per_point = channel_length as f64 / width;
+
+        let clamp = |mut view: Self| {
+            view.samples_per_point = view.samples_per_point.clamp(
+                Self::MIN_SAMPLES_PER_POINT.min(fit_samples_per_point),
+                fit_samples_per_point,
+            );
+            view.start = view.start.clamp(
+                0.0,
+                (channel_length as f64 - view.samples_per_point * width).max(0.0),
+            );
+            view
+        };
+
+        let mut view = clamp(ui.data(|data| data.get_temp::<Self>(id)).unwrap_or(Self {
+            start: 0.0,
+            samples_per_point: fit_samples_per_point,
+        }));
+
+        if response.hovered() && response.enabled() {
+            let (scroll_delta, zoom_delta) =
+                ui.input(|input| (input.smooth_scroll_delta, input.zoom_delta()));
+
+            // Scrolling past the ends of the zoom or the buffer is left to
+            // whatever contains the timeline, e.g. a scroll area
+            let mut consumed = Vec2::ZERO;
+
+            if let Some(pointer_pos) = response.hover_pos() {
+                let zoom = f64::from(zoom_delta * (scroll_delta.y * Self::WHEEL_ZOOM_SPEED).exp());
+
+                // Keep the sample under the pointer in place, in whichever
+                // channel the pointer is over
+                let anchor_x = f64::from(pointer_pos.x - response.rect.left()) % width;
+                let anchor = view.start + anchor_x * view.samples_per_point;
+
+                let mut zoomed = view;
+                zoomed.samples_per_point /= zoom;
+                zoomed.start = anchor - anchor_x * zoomed.samples_per_point;
+                let zoomed = clamp(zoomed);
+
+                if zoomed.samples_per_point != view.samples_per_point {
+                    consumed.y = scroll_delta.y;
+                }
+                view = zoomed;
+            }
+
+            let mut panned = view;
+            panned.start -= f64::from(scroll_delta.x) * view.samples_per_point;
+            let panned = clamp(panned);
+
+            if panned.start != view.start {
+                consumed.x = scroll_delta.x;
+            }
+            view = panned;
+
+            if consumed != Vec2::ZERO {
+                ui.input_mut(|input| input.smooth_scroll_delta -= consumed);
+            }
+        }
+
+        if response.dragged() {
+            view.start -= f64::from(response.drag_delta().x) * view.samples_per_point;
+            view = clamp(view);
+        }
+
+        ui.data_mut(|data| data.insert_temp(id, view));
+
+        view
+    }
+}
+
+/// Peaks built by timelines that weren't given any, rebuilt when the buffer
+/// is replaced or its generation changes.
+#[derive(Clone)]
+struct PeaksCache {
+    key: PeaksKey,
+    peaks: Arc<WaveformPeaks>,
+}
+
+/// What the cached peaks were built from. Sources have no address that
+/// survives between frames, so they only go by their generation.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct PeaksKey {
+    buffer_ptr: Option<usize>,
+    len: usize,
+    channels: usize,
+    buffer_layout: BufferLayout,
+    generation: Option<u64>,
+}
+
+impl PeaksCache {
+    fn get(
+        ui: &Ui,
+        id: Id,
+        key: PeaksKey,
+        build: impl FnOnce() -> WaveformPeaks,
+    ) -> Arc<WaveformPeaks> {
+        let id = id.with("peaks");
+
+        if let Some(cache) = ui
+            .data(|data| data.get_temp::<Self>(id))
+            .filter(|cache| cache.key == key)
+        {
+            return cache.peaks;
+        }
+
+        let peaks = Arc::new(build());
+
+        ui.data_mut(|data| {
+            data.insert_temp(
+                id,
+                Self {
+                    key,
+                    peaks: peaks.clone(),
+                },
+            );
+        });
+
+        peaks
+    }
+}
+
+/// Where a waveform reads its samples from.
+enum Samples<'a, SampleType> {
+    Buffer(&'a [SampleType]),
+    Source(&'a dyn SampleSource<SampleType>),
+}
+
+impl<SampleType> Samples<'_, SampleType> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Buffer(buffer) => buffer.len(),
+            Self::Source(source) => source.len(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 pub struct WaveformDisplayWidget<'a, SampleType>
 where
     SampleType: SampleRange<SampleType>,
 {
     get_set_value: GetSetValue<'a>,
-    buffer: Option<&'a [SampleType]>,
+    samples: Option<Samples<'a, SampleType>>,
     buffer_layout: BufferLayout,
     channels: usize,
     window_size: Option<usize>,
@@ -114,6 +387,25 @@ where
     channel_names: Option<Vec<String>>,
     show_header: bool,
     waveform_resolution: usize,
+    free_run: bool,
+    trigger_edge: SignalEdge,
+    trigger_level: Option<f32>,
+    trigger_hysteresis: f32,
+    trigger_holdoff: usize,
+    trigger_channel: usize,
+    timeline: bool,
+    peaks: Option<&'a WaveformPeaks>,
+    generation: Option<u64>,
+    channel_layout: ChannelLayout,
+    mid_side: bool,
+    correlation_meter: bool,
+    show_graticule: bool,
+    graticule_divisions: (usize, usize),
+    graticule_subdivisions: usize,
+    sample_rate: Option<f32>,
+    vertical_unit: VerticalUnit,
+    gain: Option<&'a mut f32>,
+    offset: Option<&'a mut f32>,
 }
 
 impl<'a, SampleType> WaveformDisplayWidget<'a, SampleType>
@@ -132,7 +424,7 @@ where
     pub fn from_get_set(get_set_value: impl 'a + FnMut(Option<bool>) -> bool) -> Self {
         Self {
             get_set_value: Box::new(get_set_value),
-            buffer: None,
+            samples: None,
             buffer_layout: BufferLayout::Interleaved,
             channels: 1,
             window_size: None,
@@ -142,11 +434,39 @@ where
             channel_names: None,
             show_header: true,
             waveform_resolution: 1,
+            free_run: false,
+            trigger_edge: SignalEdge::RisingEdge,
+            trigger_level: None,
+            trigger_hysteresis: 0.0,
+            trigger_holdoff: 0,
+            trigger_channel: 0,
+            timeline: false,
+            peaks: None,
+            generation: None,
+            channel_layout: ChannelLayout::SideBySide,
+            mid_side: false,
+            correlation_meter: true,
+            show_graticule: false,
+            graticule_divisions: (10, 8),
+            graticule_subdivisions: 5,
+            sample_rate: None,
+            vertical_unit: VerticalUnit::FullScale,
+            gain: None,
+            offset: None,
         }
     }
 
     pub fn buffer(mut self, buffer: &'a [SampleType]) -> Self {
-        self.buffer = Some(buffer);
+        self.samples = Some(Samples::Buffer(buffer));
+        self
+    }
+
+    /// Reads the samples through `source` instead of from a `buffer()`. Sets
+    /// the channel count and buffer layout to those of the source.
+    pub fn source(mut self, source: &'a dyn SampleSource<SampleType>) -> Self {
+        self.channels = source.channels();
+        self.buffer_layout = source.buffer_layout();
+        self.samples = Some(Samples::Source(source));
         self
     }
 
@@ -160,11 +480,30 @@ where
         self
     }
 
+    pub fn try_channels(self, channels: usize) -> Result<Self, ValidationError> {
+        if channels == 0 {
+            return Err(ValidationError::ChannelCount(channels));
+        }
+        Ok(self.channels(channels))
+    }
+
     pub fn window_size(mut self, window_size: usize) -> Self {
         self.window_size = Some(window_size);
         self
     }
 
+    /// Like `window_size()`, but returns an error for odd window sizes. Sizes
+    /// larger than the buffer are only caught by `validate()`.
+    pub fn try_window_size(self, window_size: usize) -> Result<Self, ValidationError> {
+        if !window_size.is_multiple_of(2) {
+            return Err(ValidationError::WindowSize {
+                window_size,
+                channel_length: self.channel_length().unwrap_or(window_size),
+            });
+        }
+        Ok(self.window_size(window_size))
+    }
+
     pub fn width(mut self, width: impl Into<f32>) -> Self {
         self.width = width.into();
         self
@@ -194,6 +533,499 @@ where
         self.waveform_resolution = waveform_resolution;
         self
     }
+
+    pub fn try_waveform_resolution(
+        self,
+        waveform_resolution: usize,
+    ) -> Result<Self, ValidationError> {
+        if waveform_resolution == 0 {
+            return Err(ValidationError::WaveformResolution(waveform_resolution));
+        }
+        Ok(self.waveform_resolution(waveform_resolution))
+    }
+
+    /// Centers the window in the buffer instead of on a trigger point.
+    pub fn free_run(mut self, free_run: bool) -> Self {
+        self.free_run = free_run;
+        self
+    }
+
+    pub fn trigger_edge(mut self, trigger_edge: SignalEdge) -> Self {
+        self.trigger_edge = trigger_edge;
+        self
+    }
+
+    /// Trigger level in sample units, the zero of `SampleType` by default.
+    pub fn trigger_level(mut self, trigger_level: f32) -> Self {
+        self.trigger_level = Some(trigger_level);
+        self
+    }
+
+    /// How far the signal has to move away from the trigger level, against
+    /// the trigger edge, before it can trigger again. Rejects noise around
+    /// the trigger level.
+    pub fn trigger_hysteresis(mut self, trigger_hysteresis: f32) -> Self {
+        self.trigger_hysteresis = trigger_hysteresis;
+        self
+    }
+
+    pub fn try_trigger_hysteresis(self, trigger_hysteresis: f32) -> Result<Self, ValidationError> {
+        validate_not_negative("trigger hysteresis", trigger_hysteresis)?;
+        Ok(self.trigger_hysteresis(trigger_hysteresis))
+    }
+
+    /// Number of samples after a trigger point in which the signal can't
+    /// trigger again, for waveforms crossing the trigger level several times
+    /// per period.
+    pub fn trigger_holdoff(mut self, trigger_holdoff: usize) -> Self {
+        self.trigger_holdoff = trigger_holdoff;
+        self
+    }
+
+    /// The channel whose trigger point positions the window of every channel.
+    pub fn trigger_channel(mut self, trigger_channel: usize) -> Self {
+        self.trigger_channel = trigger_channel;
+        self
+    }
+
+    pub fn try_trigger_channel(self, trigger_channel: usize) -> Result<Self, ValidationError> {
+        if trigger_channel >= self.channels {
+            return Err(ValidationError::TriggerChannel {
+                channel: trigger_channel,
+                channels: self.channels,
+            });
+        }
+        Ok(self.trigger_channel(trigger_channel))
+    }
+
+    /// Shows the whole buffer on a zoomable, scrollable timeline instead of
+    /// a triggered window. The mouse wheel and pinch gestures zoom, dragging
+    /// and horizontal scrolling pan. XY layouts always show a window.
+    pub fn timeline(mut self, timeline: bool) -> Self {
+        self.timeline = timeline;
+        self
+    }
+
+    /// Peaks of the buffer for the timeline. Without them the timeline builds
+    /// its own when the buffer is replaced or its `generation` changes.
+    pub fn peaks(mut self, peaks: &'a WaveformPeaks) -> Self {
+        self.peaks = Some(peaks);
+        self
+    }
+
+    /// Counter bumped by the caller whenever the samples change in place, so
+    /// that the timeline rebuilds its own peaks. Without it only replacing
+    /// the buffer does, and sources fall back to `SampleSource::generation()`.
+    pub fn generation(mut self, generation: u64) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    pub fn channel_layout(mut self, channel_layout: ChannelLayout) -> Self {
+        self.channel_layout = channel_layout;
+        self
+    }
+
+    /// Rotates XY plots by 45° to show mid and side, so that mono signals are
+    /// vertical like on a goniometer.
+    pub fn mid_side(mut self, mid_side: bool) -> Self {
+        self.mid_side = mid_side;
+        self
+    }
+
+    /// Shows the phase correlation of each channel pair under XY plots.
+    pub fn correlation_meter(mut self, correlation_meter: bool) -> Self {
+        self.correlation_meter = correlation_meter;
+        self
+    }
+
+    /// Draws an oscilloscope graticule with labelled axes behind waveforms.
+    /// XY plots don't have one.
+    pub fn show_graticule(mut self, show_graticule: bool) -> Self {
+        self.show_graticule = show_graticule;
+        self
+    }
+
+    /// Number of major graticule divisions, horizontally and vertically.
+    pub fn graticule_divisions(mut self, horizontal: usize, vertical: usize) -> Self {
+        self.graticule_divisions = (horizontal, vertical);
+        self
+    }
+
+    /// Number of minor ticks per major division, along the center axes.
+    pub fn graticule_subdivisions(mut self, graticule_subdivisions: usize) -> Self {
+        self.graticule_subdivisions = graticule_subdivisions;
+        self
+    }
+
+    /// Labels the time axis in seconds rather than samples.
+    pub fn sample_rate(mut self, sample_rate: f32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    pub fn vertical_unit(mut self, vertical_unit: VerticalUnit) -> Self {
+        self.vertical_unit = vertical_unit;
+        self
+    }
+
+    /// Vertical gain, changed by scrolling inside the display. Timelines,
+    /// where scrolling zooms, change it while Alt is held.
+    pub fn gain(mut self, gain: &'a mut f32) -> Self {
+        self.gain = Some(gain);
+        self
+    }
+
+    /// Vertical offset in full scale units, changed by dragging vertically
+    /// inside the display.
+    pub fn offset(mut self, offset: &'a mut f32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    fn vertical_scale(&self) -> VerticalScale {
+        VerticalScale {
+            gain: self.gain.as_deref().copied().unwrap_or(1.0),
+            offset: self.offset.as_deref().copied().unwrap_or(0.0),
+        }
+    }
+
+    fn channel_length(&self) -> Option<usize> {
+        self.samples
+            .as_ref()
+            .map(|samples| samples.len() / self.channels.max(1))
+    }
+
+    /// Half the channel, rounded down to an even size.
+    fn default_window_size(channel_length: usize) -> usize {
+        channel_length / 4 * 2
+    }
+
+    /// Checks the configuration against the buffer. An invalid configuration
+    /// is shown as an error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.channels == 0 {
+            return Err(ValidationError::ChannelCount(self.channels));
+        }
+
+        if self.waveform_resolution == 0 {
+            return Err(ValidationError::WaveformResolution(
+                self.waveform_resolution,
+            ));
+        }
+
+        if self.channel_layout == ChannelLayout::Xy && !self.channels.is_multiple_of(2) {
+            return Err(ValidationError::XyChannelCount(self.channels));
+        }
+
+        if self.trigger_channel >= self.channels {
+            return Err(ValidationError::TriggerChannel {
+                channel: self.trigger_channel,
+                channels: self.channels,
+            });
+        }
+
+        validate_not_negative("trigger hysteresis", self.trigger_hysteresis)?;
+
+        validate_positive("horizontal divisions", self.graticule_divisions.0 as f32)?;
+        validate_positive("vertical divisions", self.graticule_divisions.1 as f32)?;
+        validate_positive("subdivisions", self.graticule_subdivisions as f32)?;
+
+        if let Some(sample_rate) = self.sample_rate {
+            validate_positive("sample rate", sample_rate)?;
+        }
+
+        if let VerticalUnit::Volts { full_scale } = self.vertical_unit {
+            validate_positive("full scale voltage", full_scale)?;
+        }
+
+        validate_positive("gain", self.vertical_scale().gain)?;
+
+        if let Some(ref channel_names) = self.channel_names {
+            if channel_names.len() != self.channels {
+                return Err(ValidationError::ChannelNameCount {
+                    names: channel_names.len(),
+                    channels: self.channels,
+                });
+            }
+        }
+
+        if let Some(ref samples) = self.samples {
+            if !samples.len().is_multiple_of(self.channels) {
+                return Err(ValidationError::BufferLength {
+                    length: samples.len(),
+                    channels: self.channels,
+                });
+            }
+
+            let channel_length = samples.len() / self.channels;
+            if !channel_length.is_multiple_of(2) || channel_length < 4 {
+                return Err(ValidationError::ChannelLength(channel_length));
+            }
+
+            let window_size = self
+                .window_size
+                .unwrap_or(Self::default_window_size(channel_length));
+            if !window_size.is_multiple_of(2) || window_size > channel_length {
+                return Err(ValidationError::WindowSize {
+                    window_size,
+                    channel_length,
+                });
+            }
+
+            if let Some(peaks) = self.peaks {
+                if peaks.channels() != self.channels || peaks.channel_length() != channel_length {
+                    return Err(ValidationError::PeaksMismatch);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, SampleType> WaveformDisplayWidget<'a, SampleType>
+where
+    SampleType: SampleRange<SampleType> + Into<f32> + Copy,
+{
+    /// Timelines zoomed in to at least 4 points per sample show every sample.
+    const SAMPLE_DOTS_MAX_SAMPLES_PER_POINT: f64 = 1.0 / 4.0;
+
+    /// Gain factor per point of mouse wheel scrolling.
+    const WHEEL_GAIN_SPEED: f32 = 1.0 / 200.0;
+
+    /// Paints the graticule behind a waveform, labelled with the time of each
+    /// major division along the bottom and its level along the left.
+    fn paint_graticule(
+        &self,
+        ui: &Ui,
+        rect: Rect,
+        start_sample: f64,
+        samples_per_point: f64,
+        vertical_scale: VerticalScale,
+    ) {
+        let painter = ui.painter_at(rect);
+        let stroke = ui.style().visuals.widgets.noninteractive.bg_stroke;
+        let text_color = ui.style().visuals.weak_text_color();
+        let font_id = TextStyle::Small.resolve(ui.style());
+
+        let (horizontal_divisions, vertical_divisions) = self.graticule_divisions;
+        let division_size = vec2(
+            rect.width() / horizontal_divisions as f32,
+            rect.height() / vertical_divisions as f32,
+        );
+        let division_x = |division: usize| rect.left() + division_size.x * division as f32;
+        let division_y = |division: usize| rect.top() + division_size.y * division as f32;
+
+        for division in 1..horizontal_divisions {
+            painter.vline(division_x(division), rect.y_range(), stroke);
+        }
+
+        for division in 1..vertical_divisions {
+            painter.hline(rect.x_range(), division_y(division), stroke);
+        }
+
+        // Minor ticks along the center axes
+        let tick_length = division_size.min_elem() / 8.0;
+        let subdivision_size = division_size / self.graticule_subdivisions as f32;
+        let center = rect.center();
+
+        for subdivision in 1..(horizontal_divisions * self.graticule_subdivisions) {
+            let x = rect.left() + subdivision_size.x * subdivision as f32;
+            painter.vline(
+                x,
+                (center.y - tick_length)..=(center.y + tick_length),
+                stroke,
+            );
+        }
+
+        for subdivision in 1..(vertical_divisions * self.graticule_subdivisions) {
+            let y = rect.top() + subdivision_size.y * subdivision as f32;
+            painter.hline(
+                (center.x - tick_length)..=(center.x + tick_length),
+                y,
+                stroke,
+            );
+        }
+
+        let samples_per_division = f64::from(division_size.x) * samples_per_point;
+        let format_time = |samples: f64| match self.sample_rate.map(f64::from) {
+            Some(sample_rate) => format_si(
+                samples / sample_rate,
+                samples_per_division / sample_rate,
+                "s",
+            ),
+            None => format_step(samples, samples_per_division),
+        };
+
+        for division in 1..horizontal_divisions {
+            painter.text(
+                pos2(division_x(division), rect.bottom()),
+                Align2::CENTER_BOTTOM,
+                format_time(start_sample + samples_per_division * division as f64),
+                font_id.clone(),
+                text_color,
+            );
+        }
+
+        let level_step = 2.0 / vertical_divisions as f32 / vertical_scale.gain;
+
+        for division in 1..vertical_divisions {
+            let y = division_y(division);
+            let level = vertical_scale.unapply(remap(y, rect.bottom_up_range(), -1.0..=1.0));
+
+            painter.text(
+                pos2(rect.left() + tick_length, y),
+                Align2::LEFT_BOTTOM,
+                self.vertical_unit.format(level, level_step),
+                font_id.clone(),
+                text_color,
+            );
+        }
+    }
+
+    /// Paints the visible part of a timeline: min/max envelopes of the peaks
+    /// when zoomed out, the samples themselves when zoomed in.
+    #[allow(clippy::too_many_arguments)]
+    fn paint_timeline(
+        &self,
+        painter: &Painter,
+        rect: Rect,
+        source: &dyn SampleSource<SampleType>,
+        peaks: &WaveformPeaks,
+        view: TimelineView,
+        channel_id: usize,
+        stroke: Stroke,
+        vertical_scale: VerticalScale,
+    ) {
+        let channel_length = peaks.channel_length();
+
+        let x_to_index = |x: f32| view.start + f64::from(x - rect.left()) * view.samples_per_point;
+        let index_to_x = |index: usize| {
+            rect.left() + ((index as f64 - view.start) / view.samples_per_point) as f32
+        };
+        let sample_to_y = |sample: f32| vertical_scale.sample_to_y::<SampleType>(sample, rect);
+
+        if let Some(level) = peaks.level_for(channel_id, view.samples_per_point) {
+            let mut mesh = Mesh::default();
+
+            for column in 0..rect.width().ceil() as usize {
+                let x = rect.left() + column as f32;
+                let first = x_to_index(x).floor() as usize;
+                let last = (x_to_index(x + 1.0).ceil() as usize).min(channel_length);
+
+                let Some((min, max)) = peaks.peaks(channel_id, level, first..last) else {
+                    break;
+                };
+
+                // Flat stretches stay at least a stroke high
+                let (top, bottom) = (sample_to_y(max), sample_to_y(min));
+                let padding = ((stroke.width - (bottom - top)) / 2.0).max(0.0);
+
+                let index = mesh.vertices.len() as u32;
+                mesh.colored_vertex(pos2(x + 0.5, top - padding), stroke.color);
+                mesh.colored_vertex(pos2(x + 0.5, bottom + padding), stroke.color);
+
+                if index > 0 {
+                    mesh.add_triangle(index - 2, index - 1, index);
+                    mesh.add_triangle(index - 1, index, index + 1);
+                }
+            }
+
+            painter.add(Shape::mesh(mesh));
+        } else {
+            let first = x_to_index(rect.left()).floor().max(0.0) as usize;
+            let last = (x_to_index(rect.right()).ceil() as usize + 1).min(channel_length);
+
+            let points = (first..last)
+                .map(|index| {
+                    let sample = source.channel_sample(channel_id, index).into();
+                    pos2(index_to_x(index), sample_to_y(sample))
+                })
+                .collect_vec();
+
+            if view.samples_per_point <= Self::SAMPLE_DOTS_MAX_SAMPLES_PER_POINT {
+                for &point in &points {
+                    painter.circle_filled(point, stroke.width + 1.0, stroke.color);
+                }
+            }
+
+            painter.add(Shape::line(points, stroke));
+        }
+    }
+
+    /// Finds the first trigger point that leaves room for a whole window
+    /// around it. Scanning stops there, or once no window would fit anymore.
+    fn find_trigger_point(
+        &self,
+        source: &dyn SampleSource<SampleType>,
+        window_size: usize,
+    ) -> Option<usize> {
+        let level = self.trigger_level.unwrap_or(SampleType::ZERO.into());
+        let channel_length = source.channel_length();
+        let window_center_valid_range = (window_size / 2)..=(channel_length - (window_size / 2));
+
+        let mut armed = false;
+        let mut holdoff_end = 0;
+
+        // Samples before the first valid window center still arm the trigger
+        // and start holdoffs.
+        let scan_end = channel_length.min(window_center_valid_range.end() + 1);
+
+        for index in 0..scan_end {
+            if index < holdoff_end {
+                continue;
+            }
+
+            let sample: f32 = source.channel_sample(self.trigger_channel, index).into();
+
+            let (arm, fire) = match self.trigger_edge {
+                SignalEdge::RisingEdge => {
+                    (sample < level - self.trigger_hysteresis, sample >= level)
+                }
+                SignalEdge::FallingEdge => {
+                    (sample >= level + self.trigger_hysteresis, sample < level)
+                }
+            };
+
+            if arm {
+                armed = true;
+            } else if armed && fire {
+                if window_center_valid_range.contains(&index) {
+                    return Some(index);
+                }
+
+                armed = false;
+                holdoff_end = index + self.trigger_holdoff;
+            }
+        }
+
+        None
+    }
+}
+
+/// Distinct colors for overlaid channels, spread around the hue circle by the
+/// golden ratio.
+fn channel_color(channel_id: usize) -> Color32 {
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+    let hue = (channel_id as f32 * golden_ratio).fract();
+    Hsva::new(hue, 0.85, 0.5, 1.0).into()
+}
+
+/// Phase correlation of a pair of signals, from `-1.0` for signals out of
+/// phase to `1.0` for identical ones. Silence counts as uncorrelated.
+fn phase_correlation(samples: impl Iterator<Item = (f32, f32)>) -> f32 {
+    let (product, energy_a, energy_b) =
+        samples.fold((0.0, 0.0, 0.0), |(product, energy_a, energy_b), (a, b)| {
+            (product + a * b, energy_a + a * a, energy_b + b * b)
+        });
+
+    let energy = (energy_a * energy_b).sqrt();
+    if energy > 0.0 {
+        (product / energy).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
 }
 
 impl<'a, SampleType> Widget for WaveformDisplayWidget<'a, SampleType>
@@ -201,8 +1033,21 @@ where
     SampleType: SampleRange<SampleType> + Into<f32> + Copy + PartialOrd,
 {
     fn ui(mut self, ui: &mut Ui) -> Response {
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        self.timeline &= self.channel_layout != ChannelLayout::Xy;
+
         let desired_size = vec2(self.width, self.height);
-        let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::click());
+        let (rect, mut response) = ui.allocate_exact_size(
+            desired_size,
+            if self.timeline || self.offset.is_some() {
+                Sense::click_and_drag()
+            } else {
+                Sense::click()
+            },
+        );
 
         if response.clicked() {
             let track_enabled = get(&mut self.get_set_value);
@@ -210,10 +1055,61 @@ where
             response.mark_changed();
         }
 
+        // Vertical controls, like the knobs of a bench scope
+        if response.hovered() && response.enabled() {
+            let timeline = self.timeline;
+
+            if let Some(gain) = self.gain.as_deref_mut() {
+                // Scrolling zooms timelines unless Alt is held
+                let scroll = ui.input_mut(|input| {
+                    if timeline && !input.modifiers.alt {
+                        0.0
+                    } else {
+                        std::mem::take(&mut input.smooth_scroll_delta.y)
+                    }
+                });
+
+                if scroll != 0.0 {
+                    *gain *= (scroll * Self::WHEEL_GAIN_SPEED).exp();
+                    response.mark_changed();
+                }
+            }
+        }
+
+        if let Some(offset) = self.offset.as_deref_mut() {
+            let drag_delta = response.drag_delta().y;
+
+            if drag_delta != 0.0 && response.enabled() {
+                let lane_height = if self.channel_layout == ChannelLayout::Stacked {
+                    rect.height() / self.channels as f32
+                } else {
+                    rect.height()
+                };
+
+                *offset -= drag_delta / (lane_height / 2.0);
+                response.mark_changed();
+            }
+        }
+
+        let vertical_scale = self.vertical_scale();
+
+        let timeline_view = self
+            .channel_length()
+            .filter(|_| self.timeline)
+            .map(|channel_length| {
+                let lane_width = if self.channel_layout == ChannelLayout::SideBySide {
+                    rect.width() / self.channels as f32
+                } else {
+                    rect.width()
+                };
+
+                TimelineView::update(ui, &response, channel_length, lane_width)
+            });
+
         if ui.is_rect_visible(rect) {
             let track_enabled = get(&mut self.get_set_value);
 
-            let visuals = *ui.style().interact(&response);
+            let visuals = interact_visuals(ui, &response, true);
 
             let font_id = FontSelection::Default.resolve(ui.style());
 
@@ -223,6 +1119,8 @@ where
                 ui.style().noninteractive().text_color()
             };
 
+            let trigger_color = ui.style().visuals.selection.stroke.color;
+
             ui.painter().rect(
                 rect,
                 visuals.corner_radius,
@@ -231,178 +1129,444 @@ where
                 StrokeKind::Middle,
             );
 
-            if let Some(buffer) = self.buffer {
-                assert_eq!(buffer.len() % self.channels, 0);
+            let slice_source;
+            let source: Option<&dyn SampleSource<SampleType>> = match self.samples {
+                Some(Samples::Buffer(buffer)) => {
+                    slice_source = SliceSource::new(buffer, self.channels, self.buffer_layout);
+                    Some(&slice_source)
+                }
+                Some(Samples::Source(source)) => Some(source),
+                None => None,
+            };
 
-                let channel_buffer_length = buffer.len() / self.channels;
-                assert_eq!(channel_buffer_length.rem_euclid(2), 0);
+            if let Some(source) = source {
+                let channel_buffer_length = source.channel_length();
+
+                let window_size = self
+                    .window_size
+                    .unwrap_or(Self::default_window_size(channel_buffer_length));
+
+                let cached_peaks;
+                let peaks = match (self.peaks, &self.samples) {
+                    (Some(peaks), _) => Some(peaks),
+                    (None, _) if !self.timeline => None,
+                    (None, Some(Samples::Buffer(buffer))) => {
+                        let key = PeaksKey {
+                            buffer_ptr: Some(buffer.as_ptr() as usize),
+                            len: buffer.len(),
+                            channels: self.channels,
+                            buffer_layout: self.buffer_layout,
+                            generation: self.generation,
+                        };
+                        cached_peaks = PeaksCache::get(ui, response.id, key, || {
+                            WaveformPeaks::new(buffer, self.channels, self.buffer_layout)
+                        });
+                        Some(&*cached_peaks)
+                    }
+                    (None, _) => {
+                        cached_peaks = match self.generation.or(source.generation()) {
+                            Some(generation) => {
+                                let key = PeaksKey {
+                                    buffer_ptr: None,
+                                    len: source.len(),
+                                    channels: source.channels(),
+                                    buffer_layout: source.buffer_layout(),
+                                    generation: Some(generation),
+                                };
+                                PeaksCache::get(ui, response.id, key, || {
+                                    WaveformPeaks::from_source(source)
+                                })
+                            }
+                            // Without a generation there's no telling whether
+                            // the source changed since the last frame
+                            None => Arc::new(WaveformPeaks::from_source(source)),
+                        };
+                        Some(&*cached_peaks)
+                    }
+                };
 
-                if let Some(ref channel_names) = self.channel_names {
-                    assert_eq!(channel_names.len(), self.channels);
-                }
+                let trigger_point = if self.free_run || self.timeline {
+                    None
+                } else {
+                    self.find_trigger_point(source, window_size)
+                };
 
-                let render_channel =
-                    |rect: Rect, channel_buffer: &[SampleType], channel_name: &Option<String>| {
-                        let header_height = font_id.size;
-                        let waveform_vertical_margin = 4.0;
+                // Untriggered waveforms run free, like an oscilloscope in auto mode
+                let window_center = trigger_point.unwrap_or(channel_buffer_length / 2);
 
-                        // Header
-                        if self.show_header {
-                            let header_rect = {
-                                let mut tmp = rect;
-                                tmp.set_height(header_height);
-                                tmp
-                            };
+                let header_height = font_id.size;
+                let waveform_vertical_margin = 4.0;
 
-                            if let Some(channel_name) = channel_name {
-                                ui.painter().text(
-                                    header_rect.center(),
-                                    Align2::CENTER_CENTER,
-                                    channel_name,
-                                    font_id.clone(),
-                                    foreground_color,
+                let channel_name = |channel_id: usize| -> Option<String> {
+                    if let Some(ref channel_names) = self.channel_names {
+                        channel_names.get(channel_id).cloned()
+                    } else {
+                        self.track_name.clone()
+                    }
+                };
+
+                let render_header =
+                    |rect: Rect, channel_name: &Option<String>, channel_ids: Range<usize>| {
+                        let header_rect = {
+                            let mut tmp = rect;
+                            tmp.set_height(header_height);
+                            tmp
+                        };
+
+                        if let Some(channel_name) = channel_name {
+                            ui.painter().text(
+                                header_rect.center(),
+                                Align2::CENTER_CENTER,
+                                channel_name,
+                                font_id.clone(),
+                                foreground_color,
+                            );
+                        }
+
+                        if track_enabled {
+                            ui.painter().text(
+                                header_rect.right_center(),
+                                Align2::RIGHT_CENTER,
+                                '\u{1F508}',
+                                font_id.clone(),
+                                foreground_color,
+                            );
+                        }
+
+                        let mut legend_left = header_rect.left() + header_height / 2.0;
+
+                        // Trigger indicator, hollow while not triggered
+                        if channel_ids.contains(&self.trigger_channel)
+                            && !self.free_run
+                            && !self.timeline
+                        {
+                            let indicator_radius = header_height / 4.0;
+                            let indicator_center = pos2(legend_left, header_rect.center().y);
+
+                            if trigger_point.is_some() {
+                                ui.painter().circle_filled(
+                                    indicator_center,
+                                    indicator_radius,
+                                    trigger_color,
+                                );
+                            } else {
+                                ui.painter().circle_stroke(
+                                    indicator_center,
+                                    indicator_radius,
+                                    Stroke::new(visuals.fg_stroke.width, foreground_color),
                                 );
                             }
 
-                            if track_enabled {
-                                ui.painter().text(
-                                    header_rect.right_center(),
-                                    Align2::RIGHT_CENTER,
-                                    '\u{1F508}',
+                            legend_left += header_height;
+                        }
+
+                        // Overlaid channels are told apart by the colors of their names
+                        if channel_ids.len() > 1 {
+                            for channel_id in channel_ids {
+                                let channel_name = self
+                                    .channel_names
+                                    .as_ref()
+                                    .and_then(|channel_names| {
+                                        channel_names.get(channel_id).cloned()
+                                    })
+                                    .unwrap_or_else(|| (channel_id + 1).to_string());
+
+                                let text_rect = ui.painter().text(
+                                    pos2(legend_left, header_rect.center().y),
+                                    Align2::LEFT_CENTER,
+                                    channel_name,
                                     font_id.clone(),
-                                    foreground_color,
+                                    channel_color(channel_id),
                                 );
+
+                                legend_left = text_rect.right() + header_height / 2.0;
                             }
                         }
+                    };
 
-                        // Waveform
-                        {
-                            let waveform_rect = if self.show_header {
-                                let mut tmp = rect;
-                                tmp = tmp.translate(vec2(0.0, header_height));
-                                tmp.set_height(rect.height() - header_height);
-                                tmp.shrink2(vec2(0.0, waveform_vertical_margin))
-                            } else {
-                                rect.shrink2(vec2(0.0, waveform_vertical_margin))
+                let waveform_rect = |rect: Rect| {
+                    if self.show_header {
+                        let mut tmp = rect;
+                        tmp = tmp.translate(vec2(0.0, header_height));
+                        tmp.set_height(rect.height() - header_height);
+                        tmp.shrink2(vec2(0.0, waveform_vertical_margin))
+                    } else {
+                        rect.shrink2(vec2(0.0, waveform_vertical_margin))
+                    }
+                };
+
+                let render_channels =
+                    |rect: Rect, channel_ids: Range<usize>, channel_name: &Option<String>| {
+                        if self.show_header {
+                            render_header(rect, channel_name, channel_ids.clone());
+                        }
+
+                        let waveform_rect = waveform_rect(rect);
+
+                        let index_to_x = |index: usize| {
+                            remap_clamp(
+                                index as f32,
+                                0.0..=(window_size as f32 - 1.0),
+                                waveform_rect.x_range(),
+                            )
+                        };
+
+                        let sample_to_y = |sample: f32| {
+                            vertical_scale.sample_to_y::<SampleType>(sample, waveform_rect)
+                        };
+
+                        if self.show_graticule {
+                            let (start_sample, samples_per_point) = match timeline_view {
+                                Some(view) => (view.start, view.samples_per_point),
+                                // Time counts from the trigger point
+                                None => (
+                                    -((window_size / 2) as f64),
+                                    (window_size as f64 - 1.0) / f64::from(waveform_rect.width()),
+                                ),
                             };
 
-                            let window_size = self.window_size.unwrap_or(channel_buffer_length / 2); // Default window size
-                            assert!(window_size <= channel_buffer_length);
-                            assert_eq!(window_size.rem_euclid(2), 0);
-
-                            let window_center_valid_range =
-                                (window_size / 2)..=(channel_buffer_length - (window_size / 2));
-
-                            let left_signal_edge_delta = channel_buffer
-                                [..=(channel_buffer_length / 2) + 1]
-                                .iter()
-                                .rev()
-                                .tuple_windows()
-                                .map(|(a, b)| (b, a))
-                                .enumerate()
-                                .map(|(delta, (a, b))| (delta, SignalEdge::from_samples(a, b)))
-                                .find(|(_, signal_edge)| {
-                                    *signal_edge == Some(SignalEdge::RisingEdge)
-                                })
-                                .map_or(channel_buffer_length / 2, |(delta, _)| delta);
-
-                            let right_signal_edge_delta = channel_buffer
-                                [(channel_buffer_length / 2)..]
-                                .iter()
-                                .tuple_windows()
-                                .enumerate()
-                                .map(|(delta, (a, b))| (delta, SignalEdge::from_samples(a, b)))
-                                .find(|(_, signal_edge)| {
-                                    *signal_edge == Some(SignalEdge::RisingEdge)
-                                })
-                                .map_or(channel_buffer_length / 2, |(delta, _)| delta);
+                            self.paint_graticule(
+                                ui,
+                                waveform_rect,
+                                start_sample,
+                                samples_per_point,
+                                vertical_scale,
+                            );
+                        }
 
-                            let mut window_center =
-                                if right_signal_edge_delta < left_signal_edge_delta {
-                                    (channel_buffer_length / 2) + right_signal_edge_delta
-                                } else {
-                                    (channel_buffer_length / 2) - left_signal_edge_delta
-                                };
+                        ui.painter().hline(
+                            waveform_rect.x_range(),
+                            remap_clamp(
+                                vertical_scale.offset,
+                                -1.0..=1.0,
+                                waveform_rect.bottom_up_range(),
+                            ),
+                            ui.style().visuals.noninteractive().fg_stroke,
+                        );
+
+                        for channel_id in channel_ids.clone() {
+                            let stroke = if channel_ids.len() > 1 {
+                                Stroke::new(visuals.fg_stroke.width, channel_color(channel_id))
+                            } else {
+                                visuals.fg_stroke
+                            };
 
-                            if !window_center_valid_range.contains(&window_center) {
-                                window_center = channel_buffer_length / 2;
+                            if let (Some(view), Some(peaks)) = (timeline_view, peaks) {
+                                self.paint_timeline(
+                                    &ui.painter_at(rect),
+                                    waveform_rect,
+                                    source,
+                                    peaks,
+                                    view,
+                                    channel_id,
+                                    stroke,
+                                    vertical_scale,
+                                );
+                                continue;
                             }
 
-                            let waveform_points = channel_buffer[(window_center - window_size / 2)
-                                ..(window_center + window_size / 2)]
-                                .iter()
-                                .enumerate()
+                            let window_start = window_center - window_size / 2;
+                            let waveform_points = (0..window_size)
                                 .step_by(self.waveform_resolution)
-                                .map(|(index, &sample)| {
-                                    pos2(
-                                        remap_clamp(
-                                            index as f32,
-                                            0.0..=(window_size as f32 - 1.0),
-                                            waveform_rect.x_range(),
-                                        ),
-                                        remap_clamp(
-                                            sample.into(),
-                                            SampleType::DISPLAY_RANGE,
-                                            waveform_rect.bottom_up_range(),
-                                        ),
-                                    )
+                                .map(|index| {
+                                    let sample =
+                                        source.channel_sample(channel_id, window_start + index);
+                                    pos2(index_to_x(index), sample_to_y(sample.into()))
                                 })
                                 .collect_vec();
 
-                            ui.painter().line_segment(
-                                [waveform_rect.left_center(), waveform_rect.right_center()],
-                                ui.style().visuals.noninteractive().fg_stroke,
-                            );
+                            ui.painter().add(Shape::line(waveform_points, stroke));
+                        }
+
+                        // Trigger point marker
+                        if trigger_point.is_some() {
+                            let marker_size = waveform_vertical_margin + 2.0;
+                            let x = index_to_x(window_size / 2);
 
-                            ui.painter()
-                                .add(Shape::line(waveform_points, visuals.fg_stroke));
+                            ui.painter().add(Shape::convex_polygon(
+                                vec![
+                                    pos2(
+                                        x - marker_size,
+                                        waveform_rect.top() - waveform_vertical_margin,
+                                    ),
+                                    pos2(
+                                        x + marker_size,
+                                        waveform_rect.top() - waveform_vertical_margin,
+                                    ),
+                                    pos2(
+                                        x,
+                                        waveform_rect.top() + marker_size
+                                            - waveform_vertical_margin,
+                                    ),
+                                ],
+                                trigger_color,
+                                Stroke::NONE,
+                            ));
+
+                            if channel_ids.contains(&self.trigger_channel) {
+                                let level = self.trigger_level.unwrap_or(SampleType::ZERO.into());
+
+                                ui.painter().circle_filled(
+                                    pos2(x, sample_to_y(level)),
+                                    visuals.fg_stroke.width + 1.5,
+                                    trigger_color,
+                                );
+                            }
                         }
                     };
 
-                if self.channels == 1 {
-                    render_channel(rect, buffer, &self.track_name);
-                } else {
-                    for channel_id in 0..self.channels {
-                        let channel_buffer = match self.buffer_layout {
-                            BufferLayout::Planar => buffer
-                                .iter()
-                                .copied()
-                                .skip(channel_id * channel_buffer_length)
-                                .take(channel_buffer_length)
-                                .collect_vec(),
-                            BufferLayout::Interleaved => buffer
-                                .iter()
-                                .copied()
-                                .skip(channel_id)
-                                .step_by(self.channels)
-                                .take(channel_buffer_length)
-                                .collect_vec(),
+                let render_xy = |rect: Rect,
+                                 channel_ids: Range<usize>,
+                                 channel_name: &Option<String>| {
+                    if self.show_header {
+                        render_header(rect, channel_name, channel_ids.clone());
+                    }
+
+                    let window =
+                        (window_center - window_size / 2)..(window_center + window_size / 2);
+                    let normalized = |channel_id: usize| {
+                        window.clone().map(move |index| {
+                            let sample = source.channel_sample(channel_id, index);
+                            remap(sample.into(), SampleType::DISPLAY_RANGE, -1.0..=1.0)
+                        })
+                    };
+
+                    let mut plot_rect = waveform_rect(rect);
+
+                    if self.correlation_meter {
+                        let meter_rect = plot_rect
+                            .split_top_bottom_at_y(plot_rect.bottom() - header_height / 2.0)
+                            .1;
+                        plot_rect.set_bottom(meter_rect.top() - waveform_vertical_margin);
+
+                        let correlation = phase_correlation(
+                            normalized(channel_ids.start).zip(normalized(channel_ids.start + 1)),
+                        );
+
+                        ui.painter().rect_filled(
+                            meter_rect,
+                            0.0,
+                            ui.style().visuals.faint_bg_color,
+                        );
+
+                        // Negative correlation hints at phase problems
+                        let bar_color = if correlation < 0.0 {
+                            ui.style().visuals.error_fg_color
+                        } else {
+                            ui.style().visuals.selection.bg_fill
                         };
-                        assert_eq!(channel_buffer.len(), channel_buffer_length);
 
-                        let channel_rect = Rect::from_min_size(
-                            rect.left_top()
-                                + rect.size() / vec2(self.channels as f32, 1.0)
-                                    * vec2(channel_id as f32, 0.0),
-                            rect.size() / vec2(self.channels as f32, 1.0),
+                        let correlation_x =
+                            remap_clamp(correlation, -1.0..=1.0, meter_rect.x_range());
+                        ui.painter().rect_filled(
+                            Rect::from_x_y_ranges(
+                                meter_rect.center().x.min(correlation_x)
+                                    ..=meter_rect.center().x.max(correlation_x),
+                                meter_rect.y_range(),
+                            ),
+                            0.0,
+                            bar_color,
                         );
 
-                        let channel_name: Option<String> =
-                            if let Some(ref channel_names) = self.channel_names {
-                                channel_names.get(channel_id).cloned()
+                        ui.painter().line_segment(
+                            [meter_rect.center_top(), meter_rect.center_bottom()],
+                            ui.style().visuals.noninteractive().fg_stroke,
+                        );
+                    }
+
+                    let plot_rect = Rect::from_center_size(
+                        plot_rect.center(),
+                        Vec2::splat(plot_rect.width().min(plot_rect.height())),
+                    );
+
+                    ui.painter().line_segment(
+                        [plot_rect.left_center(), plot_rect.right_center()],
+                        ui.style().visuals.noninteractive().fg_stroke,
+                    );
+                    ui.painter().line_segment(
+                        [plot_rect.center_top(), plot_rect.center_bottom()],
+                        ui.style().visuals.noninteractive().fg_stroke,
+                    );
+
+                    let xy_points = normalized(channel_ids.start)
+                        .zip(normalized(channel_ids.start + 1))
+                        .step_by(self.waveform_resolution)
+                        .map(|(x, y)| {
+                            if self.mid_side {
+                                ((y - x) * FRAC_1_SQRT_2, (x + y) * FRAC_1_SQRT_2)
                             } else {
-                                self.track_name.clone()
+                                (x, y)
+                            }
+                        })
+                        .map(|(x, y)| (x * vertical_scale.gain, y * vertical_scale.gain))
+                        .map(|(x, y)| {
+                            pos2(
+                                remap_clamp(x, -1.0..=1.0, plot_rect.x_range()),
+                                remap_clamp(y, -1.0..=1.0, plot_rect.bottom_up_range()),
+                            )
+                        })
+                        .collect_vec();
+
+                    ui.painter().add(Shape::line(xy_points, visuals.fg_stroke));
+                };
+
+                let lanes = match self.channel_layout {
+                    ChannelLayout::Overlaid => 1,
+                    ChannelLayout::Xy => self.channels / 2,
+                    ChannelLayout::SideBySide | ChannelLayout::Stacked => self.channels,
+                };
+
+                for lane in 0..lanes {
+                    let lane_rect = if self.channel_layout == ChannelLayout::Stacked {
+                        let lane_height = rect.height() / lanes as f32;
+                        Rect::from_min_size(
+                            rect.left_top() + vec2(0.0, lane_height * lane as f32),
+                            vec2(rect.width(), lane_height),
+                        )
+                    } else {
+                        let lane_width = rect.width() / lanes as f32;
+                        Rect::from_min_size(
+                            rect.left_top() + vec2(lane_width * lane as f32, 0.0),
+                            vec2(lane_width, rect.height()),
+                        )
+                    };
+
+                    match self.channel_layout {
+                        ChannelLayout::Overlaid => {
+                            render_channels(lane_rect, 0..self.channels, &self.track_name);
+                        }
+                        ChannelLayout::Xy => {
+                            let channel_ids = (lane * 2)..(lane * 2 + 2);
+                            let channel_name = match self.channel_names {
+                                Some(ref channel_names) => {
+                                    Some(channel_names[channel_ids.clone()].join(" / "))
+                                }
+                                None => self.track_name.clone(),
                             };
 
-                        render_channel(channel_rect, channel_buffer.as_slice(), &channel_name);
+                            render_xy(lane_rect, channel_ids, &channel_name);
+                        }
+                        ChannelLayout::SideBySide | ChannelLayout::Stacked => {
+                            let channel_name = if self.channels == 1 {
+                                self.track_name.clone()
+                            } else {
+                                channel_name(lane)
+                            };
 
-                        if channel_id < self.channels - 1 {
-                            ui.painter().line_segment(
-                                [channel_rect.right_top(), channel_rect.right_bottom()],
-                                ui.style().visuals.noninteractive().fg_stroke,
-                            );
+                            render_channels(lane_rect, lane..(lane + 1), &channel_name);
                         }
                     }
+
+                    if lane < lanes - 1 {
+                        ui.painter().line_segment(
+                            if self.channel_layout == ChannelLayout::Stacked {
+                                [lane_rect.left_bottom(), lane_rect.right_bottom()]
+                            } else {
+                                [lane_rect.right_top(), lane_rect.right_bottom()]
+                            },
+                            ui.style().visuals.noninteractive().fg_stroke,
+                        );
+                    }
                 }
             }
         };
@@ -410,3 +1574,124 @@ where
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod trigger_tests {
+    use super::*;
+
+    /// Trigger point of the interleaved `samples` in a window of 4 samples.
+    fn trigger_point(
+        configure: impl FnOnce(WaveformDisplayWidget<'_, f32>) -> WaveformDisplayWidget<'_, f32>,
+        samples: &[f32],
+        channels: usize,
+    ) -> Option<usize> {
+        let mut track_enabled = true;
+        let widget = configure(WaveformDisplayWidget::new(&mut track_enabled).channels(channels));
+        let source = SliceSource::new(samples, channels, BufferLayout::Interleaved);
+        widget.find_trigger_point(&source, 4)
+    }
+
+    const SQUARE: [f32; 10] = [-1.0, -1.0, -1.0, 1.0, 1.0, 1.0, -1.0, -1.0, 1.0, 1.0];
+
+    #[test]
+    fn fires_on_the_selected_edge() {
+        assert_eq!(trigger_point(|widget| widget, &SQUARE, 1), Some(3));
+        assert_eq!(
+            trigger_point(
+                |widget| widget.trigger_edge(SignalEdge::FallingEdge),
+                &SQUARE,
+                1
+            ),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn fires_at_the_level() {
+        let ramp: Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
+
+        assert_eq!(
+            trigger_point(|widget| widget.trigger_level(0.55), &ramp, 1),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn hysteresis_ignores_noise() {
+        let noisy = [0.0, 0.05, -0.05, 0.05, -0.5, -0.5, 0.5, 0.5, 0.5, 0.5];
+
+        assert_eq!(trigger_point(|widget| widget, &noisy, 1), Some(3));
+        assert_eq!(
+            trigger_point(|widget| widget.trigger_hysteresis(0.1), &noisy, 1),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn skips_crossings_without_room_for_a_window() {
+        // Crossings at 1, 4 and 8, windows can be centered on 2..=6
+        let samples = [-1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0];
+
+        assert_eq!(trigger_point(|widget| widget, &samples, 1), Some(4));
+
+        // Only a crossing past the last window center
+        let late = [-1.0, -1.0, -1.0, -1.0, -1.0, -1.0, -1.0, -1.0, 1.0];
+        assert_eq!(trigger_point(|widget| widget, &late, 1), None);
+    }
+
+    #[test]
+    fn crossings_before_the_first_window_start_holdoffs() {
+        // Crossings at 1, 4 and 6, windows can be centered on 2..=8
+        let samples = [-1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, 1.0, 1.0];
+
+        assert_eq!(
+            trigger_point(|widget| widget.trigger_holdoff(2), &samples, 1),
+            Some(4)
+        );
+        assert_eq!(
+            trigger_point(|widget| widget.trigger_holdoff(4), &samples, 1),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn runs_free_without_a_crossing() {
+        assert_eq!(trigger_point(|widget| widget, &[1.0; 10], 1), None);
+        assert_eq!(trigger_point(|widget| widget, &[-1.0; 10], 1), None);
+    }
+
+    #[test]
+    fn follows_the_trigger_channel() {
+        let stereo: Vec<f32> = SQUARE.iter().flat_map(|&sample| [-1.0, sample]).collect();
+
+        assert_eq!(trigger_point(|widget| widget, &stereo, 2), None);
+        assert_eq!(
+            trigger_point(|widget| widget.trigger_channel(1), &stereo, 2),
+            Some(3)
+        );
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        assert_serde_round_trip(SignalEdge::FallingEdge);
+        assert_serde_round_trip(SignalEdge::RisingEdge);
+
+        ChannelLayout::iter().for_each(assert_serde_round_trip);
+
+        assert_serde_round_trip(VerticalUnit::FullScale);
+        assert_serde_round_trip(VerticalUnit::Volts { full_scale: 2.5 });
+        assert_serde_round_trip(VerticalUnit::DecibelsFullScale);
+    }
+}
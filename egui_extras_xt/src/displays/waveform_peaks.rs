@@ -0,0 +1,176 @@
+use std::ops::Range;
+
+use crate::displays::{BufferLayout, SampleSource, SliceSource};
+
+// ----------------------------------------------------------------------------
+
+/// Min/max peaks of every channel of a buffer, at power-of-two block sizes.
+///
+/// Timeline waveforms read the coarsest level that still resolves a pixel,
+/// so they render in time proportional to their width rather than to the
+/// length of the buffer. Building the peaks takes a pass over the buffer, so
+/// keep them around and rebuild them when the buffer changes.
+#[derive(Clone, Debug, Default)]
+pub struct WaveformPeaks {
+    channel_length: usize,
+
+    /// Indexed by channel, then level. Level `n` holds the peaks of blocks of
+    /// `2 << n` samples.
+    levels: Vec<Vec<Vec<(f32, f32)>>>,
+}
+
+impl WaveformPeaks {
+    pub fn new<SampleType>(
+        buffer: &[SampleType],
+        channels: usize,
+        buffer_layout: BufferLayout,
+    ) -> Self
+    where
+        SampleType: Into<f32> + Copy,
+    {
+        Self::from_source(&SliceSource::new(buffer, channels.max(1), buffer_layout))
+    }
+
+    pub fn from_source<SampleType>(source: &(impl SampleSource<SampleType> + ?Sized)) -> Self
+    where
+        SampleType: Into<f32> + Copy,
+    {
+        let channel_length = source.channel_length();
+
+        let levels = (0..source.channels())
+            .map(|channel_id| {
+                let sample =
+                    |index: usize| -> f32 { source.channel_sample(channel_id, index).into() };
+
+                // The last block of an odd-length channel holds one sample
+                let mut levels = vec![(0..channel_length.div_ceil(2))
+                    .map(|block| {
+                        let a = sample(block * 2);
+                        let b = if block * 2 + 1 < channel_length {
+                            sample(block * 2 + 1)
+                        } else {
+                            a
+                        };
+                        (a.min(b), a.max(b))
+                    })
+                    .collect::<Vec<_>>()];
+
+                while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+                    let next_level = level
+                        .chunks(2)
+                        .map(|pair| pair.iter().copied().reduce(merge_peaks).unwrap_or_default())
+                        .collect();
+                    levels.push(next_level);
+                }
+
+                levels
+            })
+            .collect();
+
+        Self {
+            channel_length,
+            levels,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn channel_length(&self) -> usize {
+        self.channel_length
+    }
+
+    /// The coarsest level whose blocks are no larger than `samples`.
+    pub(crate) fn level_for(&self, channel_id: usize, samples: f64) -> Option<usize> {
+        let levels = self.levels.get(channel_id)?;
+
+        (0..levels.len())
+            .rev()
+            .find(|&level| (2_usize << level) as f64 <= samples)
+    }
+
+    /// Min/max of the samples in `range`, widened to whole blocks of `level`.
+    pub(crate) fn peaks(
+        &self,
+        channel_id: usize,
+        level: usize,
+        range: Range<usize>,
+    ) -> Option<(f32, f32)> {
+        let block_size = 2 << level;
+        let blocks = &self.levels[channel_id][level];
+
+        let start = (range.start / block_size).min(blocks.len());
+        let end = range.end.div_ceil(block_size).clamp(start, blocks.len());
+
+        blocks[start..end].iter().copied().reduce(merge_peaks)
+    }
+}
+
+fn merge_peaks((min_a, max_a): (f32, f32), (min_b, max_b): (f32, f32)) -> (f32, f32) {
+    (min_a.min(min_b), max_a.max(max_b))
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_raw_samples_below_two_samples_per_block() {
+        let peaks = WaveformPeaks::new(&[0.0_f32; 16], 1, BufferLayout::Interleaved);
+
+        assert_eq!(peaks.level_for(0, 1.0), None);
+        assert_eq!(peaks.level_for(0, 1.99), None);
+        assert_eq!(peaks.level_for(0, 2.0), Some(0));
+        assert_eq!(peaks.level_for(0, 3.99), Some(0));
+        assert_eq!(peaks.level_for(0, 4.0), Some(1));
+        assert_eq!(peaks.level_for(0, 1e9), Some(3));
+        assert_eq!(peaks.level_for(1, 2.0), None);
+    }
+
+    #[test]
+    fn keeps_the_last_sample_of_odd_channels() {
+        let peaks = WaveformPeaks::new(&[0.0_f32, 0.5, 1.0], 1, BufferLayout::Interleaved);
+
+        assert_eq!(peaks.peaks(0, 0, 2..3), Some((1.0, 1.0)));
+        assert_eq!(peaks.peaks(0, 1, 0..3), Some((0.0, 1.0)));
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod proptests {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn peaks_match_brute_force(
+            samples in vec(-1e3_f32..1e3, 0..300),
+            level in 0_usize..10,
+            start in 0_usize..320,
+            length in 0_usize..320,
+        ) {
+            let peaks = WaveformPeaks::new(&samples, 1, BufferLayout::Interleaved);
+            let level = level.min(peaks.levels[0].len() - 1);
+            let range = start..start + length;
+
+            let block_size = 2 << level;
+            let widened = (range.start / block_size * block_size).min(samples.len())
+                ..(range.end.div_ceil(block_size) * block_size).min(samples.len());
+            let expected = samples
+                .get(widened)
+                .unwrap_or_default()
+                .iter()
+                .map(|&sample| (sample, sample))
+                .reduce(merge_peaks);
+
+            prop_assert_eq!(peaks.peaks(0, level, range), expected);
+        }
+    }
+}
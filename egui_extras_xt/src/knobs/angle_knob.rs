@@ -1,11 +1,24 @@
 use std::f32::consts::TAU;
 
-use egui::{self, Response, Sense, Ui, Widget};
+use egui::{self, PointerButton, Response, Sense, Ui, Widget};
 use emath::Vec2;
-use epaint::{Shape, Stroke};
+use epaint::{Color32, Shape, Stroke};
 use strum::{Display, EnumIter};
 
-use crate::common::{snap_wrap_constrain_angle, Orientation, WidgetShape, Winding, WrapMode};
+use crate::angle::{
+    snap_wrap_constrain_angle, widget_values_to_radians, wrap_constrain_angle, AngleUnit,
+};
+use crate::common::{
+    accepts_input, angle_inline_editor, angle_slider_widget_info, animate_value, error_placeholder,
+    interact_inside, interact_visuals, validate_snap, Animation, KeyboardSteps, Orientation,
+    ScrollSteps, ValidationError, WidgetShape, Winding, WrapMode, FOCUS_RING_MARGIN,
+};
+use crate::knobs::drag_mode::{
+    knob_drag_delta, knob_drag_pointer, knob_drag_speed, pointer_angle_delta,
+};
+use crate::knobs::readout::show_readout;
+use crate::knobs::{KnobDragMode, KnobScale};
+use crate::theme::XtTheme;
 
 // ----------------------------------------------------------------------------
 
@@ -25,6 +38,7 @@ fn set(get_set_value: &mut GetSetValue<'_>, value: f32) {
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum AngleKnobPreset {
     #[strum(to_string = "Adobe Photoshop")]
     AdobePhotoshop,
@@ -95,16 +109,26 @@ impl AngleKnobPreset {
 pub struct AngleKnob<'a> {
     get_set_value: GetSetValue<'a>,
     interactive: bool,
-    diameter: f32,
+    diameter: Option<f32>,
+    drag_mode: KnobDragMode,
+    fine_speed: f32,
+    hide_pointer: bool,
+    lock_pointer: bool,
     orientation: Orientation,
     winding: Winding,
     wrap: WrapMode,
-    shape: WidgetShape,
+    unit: AngleUnit,
+    shape: Option<WidgetShape>,
+    scale: Option<KnobScale>,
     min: Option<f32>,
     max: Option<f32>,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
+    readout: Option<Box<dyn 'a + Fn(f32) -> String>>,
     animated: bool,
+    animation: Option<Animation>,
     show_axes: bool,
     axis_count: usize,
 }
@@ -123,16 +147,26 @@ impl<'a> AngleKnob<'a> {
         Self {
             get_set_value: Box::new(get_set_value),
             interactive: true,
-            diameter: 32.0,
+            diameter: None,
+            drag_mode: KnobDragMode::Rotary,
+            fine_speed: 0.1,
+            hide_pointer: false,
+            lock_pointer: false,
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
             wrap: WrapMode::Unsigned,
-            shape: WidgetShape::Circle,
+            unit: AngleUnit::Radians,
+            shape: None,
+            scale: None,
             min: None,
             max: None,
             snap: None,
             shift_snap: Some(15.0f32.to_radians()),
+            scroll_step: Some(TAU / 72.0),
+            default_value: None,
+            readout: None,
             animated: false,
+            animation: None,
             show_axes: true,
             axis_count: 4,
         }
@@ -149,7 +183,30 @@ impl<'a> AngleKnob<'a> {
     }
 
     pub fn diameter(mut self, diameter: impl Into<f32>) -> Self {
-        self.diameter = diameter.into();
+        self.diameter = Some(diameter.into());
+        self
+    }
+
+    pub fn drag_mode(mut self, drag_mode: KnobDragMode) -> Self {
+        self.drag_mode = drag_mode;
+        self
+    }
+
+    /// Drag speed multiplier while Ctrl (Cmd on macOS) is held.
+    pub fn fine_speed(mut self, fine_speed: impl Into<f32>) -> Self {
+        self.fine_speed = fine_speed.into();
+        self
+    }
+
+    pub fn hide_pointer(mut self, hide_pointer: bool) -> Self {
+        self.hide_pointer = hide_pointer;
+        self
+    }
+
+    /// Lock the pointer in place while dragging in a linear drag mode, so
+    /// drags aren't cut short by the edges of the screen.
+    pub fn lock_pointer(mut self, lock_pointer: bool) -> Self {
+        self.lock_pointer = lock_pointer;
         self
     }
 
@@ -164,7 +221,18 @@ impl<'a> AngleKnob<'a> {
     }
 
     pub fn shape(mut self, shape: WidgetShape) -> Self {
-        self.shape = shape;
+        self.shape = Some(shape);
+        self
+    }
+
+    pub fn try_shape(self, shape: WidgetShape) -> Result<Self, ValidationError> {
+        shape.validate()?;
+        Ok(self.shape(shape))
+    }
+
+    /// Tick values are in the knob's `unit`.
+    pub fn scale(mut self, scale: KnobScale) -> Self {
+        self.scale = Some(scale);
         self
     }
 
@@ -173,6 +241,13 @@ impl<'a> AngleKnob<'a> {
         self
     }
 
+    /// Unit of the value and of the limits, snap angles, scroll step and
+    /// default value. Radians unless set.
+    pub fn unit(mut self, unit: AngleUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
     pub fn min(mut self, min: Option<f32>) -> Self {
         self.min = min;
         self
@@ -193,11 +268,52 @@ impl<'a> AngleKnob<'a> {
         self
     }
 
+    /// Like `snap()`, but returns an error for a non-positive snap angle.
+    pub fn try_snap(self, snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(snap)?;
+        Ok(self.snap(snap))
+    }
+
+    /// Like `shift_snap()`, but returns an error for a non-positive snap angle.
+    pub fn try_shift_snap(self, shift_snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(shift_snap)?;
+        Ok(self.shift_snap(shift_snap))
+    }
+
+    /// Angle to turn by per mouse wheel notch, `None` ignores the wheel.
+    /// Ctrl (Cmd on macOS) scrolls in finer steps and Shift in coarser ones,
+    /// snaps take precedence.
+    pub fn scroll_step(mut self, scroll_step: Option<f32>) -> Self {
+        self.scroll_step = scroll_step;
+        self
+    }
+
+    /// Value to reset to on double-click.
+    pub fn default_value(mut self, default_value: Option<f32>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
+    /// Shows the value, formatted with `formatter`, next to the pointer
+    /// while hovering or dragging. See `knobs::readout` for common units, or
+    /// `AngleUnit::format()` when the knob works in another `unit`.
+    pub fn readout(mut self, formatter: impl 'a + Fn(f32) -> String) -> Self {
+        self.readout = Some(Box::new(formatter));
+        self
+    }
+
     pub fn animated(mut self, animated: bool) -> Self {
         self.animated = animated;
         self
     }
 
+    /// How the displayed value follows changes when `animated`. Defaults to
+    /// linear easing over the `animation_time` of the style.
+    pub fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
     pub fn show_axes(mut self, show_axes: bool) -> Self {
         self.show_axes = show_axes;
         self
@@ -207,16 +323,55 @@ impl<'a> AngleKnob<'a> {
         self.axis_count = axis_count;
         self
     }
+
+    /// Checks the configuration. An invalid configuration is shown as an
+    /// error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_snap(self.snap)?;
+        validate_snap(self.shift_snap)?;
+        self.animation
+            .as_ref()
+            .map_or(Ok(()), Animation::validate)?;
+        self.shape.as_ref().map_or(Ok(()), WidgetShape::validate)
+    }
 }
 
 impl<'a> Widget for AngleKnob<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
-        let desired_size = Vec2::splat(self.diameter);
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        self.interactive = accepts_input(ui, self.interactive);
+
+        widget_values_to_radians(
+            self.unit,
+            &mut self.get_set_value,
+            &mut [
+                &mut self.min,
+                &mut self.max,
+                &mut self.snap,
+                &mut self.shift_snap,
+                &mut self.scroll_step,
+                &mut self.default_value,
+            ],
+        );
+
+        let theme = XtTheme::current(ui);
+        let diameter = self.diameter.unwrap_or(theme.knob_diameter);
+        let shape = self.shape.as_ref().unwrap_or(&theme.angle_knob_shape);
+
+        if let Err(error) = shape.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        let scale_margin = self.scale.as_ref().map_or(0.0, |scale| scale.margin(ui));
+        let desired_size = Vec2::splat(diameter + 2.0 * scale_margin);
 
-        let (rect, mut response) = ui.allocate_exact_size(
+        let (rect, response) = ui.allocate_exact_size(
             desired_size,
             if self.interactive {
-                Sense::click_and_drag()
+                Sense::focusable_noninteractive()
             } else {
                 Sense::hover()
             },
@@ -224,11 +379,30 @@ impl<'a> Widget for AngleKnob<'a> {
 
         let rotation_matrix = self.orientation.rot2();
 
-        if response.clicked() || response.dragged() {
+        let mut response = if self.interactive {
+            interact_inside(response, Sense::click_and_drag(), |pointer_pos| {
+                shape.contains(rect.center(), diameter / 2.0, rotation_matrix, pointer_pos)
+            })
+        } else {
+            response
+        };
+
+        if response.clicked() || response.drag_started() {
+            response.request_focus();
+        }
+
+        // Ctrl+click opens the inline editor instead. Space and Enter on a
+        // focused knob click it too, but have no pointer to point it at.
+        let clicked = response.clicked_by(PointerButton::Primary)
+            && !ui.input(|input| input.modifiers.command_only());
+
+        let rotary_pointer_pos = response
+            .interact_pointer_pos()
+            .filter(|_| self.drag_mode == KnobDragMode::Rotary && (clicked || response.dragged()));
+
+        if let Some(pointer_pos) = rotary_pointer_pos {
             let prev_value = get(&mut self.get_set_value);
-            let mut new_value = (rotation_matrix.inverse()
-                * (response.interact_pointer_pos().unwrap() - rect.center()))
-            .angle()
+            let mut new_value = (rotation_matrix.inverse() * (pointer_pos - rect.center())).angle()
                 * self.winding.to_float();
 
             new_value = snap_wrap_constrain_angle(
@@ -248,15 +422,165 @@ impl<'a> Widget for AngleKnob<'a> {
             response.mark_changed();
         }
 
+        if self.drag_mode != KnobDragMode::Rotary && response.dragged() {
+            // Snapping would swallow small drags, so the unsnapped value is
+            // tracked separately.
+            let drag_value_id = response.id.with("drag_value");
+
+            let drag_value = if response.drag_started() {
+                get(&mut self.get_set_value)
+            } else {
+                ui.data(|data| data.get_temp(drag_value_id))
+                    .unwrap_or_else(|| get(&mut self.get_set_value))
+            };
+
+            let speed = knob_drag_speed(ui, self.fine_speed);
+
+            let delta = if self.drag_mode == KnobDragMode::RelativeRotary {
+                pointer_angle_delta(&response, rect.center()) * self.winding.to_float()
+            } else {
+                // Linear drags move the outline of the knob along with the
+                // pointer.
+                let drag_delta = knob_drag_delta(ui, &response, self.lock_pointer);
+                self.drag_mode
+                    .linear_delta(drag_delta, self.orientation, self.winding)
+                    / (diameter / 2.0)
+            };
+
+            let mut new_value = drag_value + speed * delta;
+
+            if let Some(min) = self.min {
+                new_value = new_value.max(min);
+            }
+
+            if let Some(max) = self.max {
+                new_value = new_value.min(max);
+            }
+
+            ui.data_mut(|data| data.insert_temp(drag_value_id, new_value));
+
+            if let Some(snap) = if ui.input(|input| input.modifiers.shift_only()) {
+                self.shift_snap
+            } else {
+                self.snap
+            } {
+                new_value = (new_value / snap).round() * snap;
+            }
+
+            set(
+                &mut self.get_set_value,
+                wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+            );
+            response.mark_changed();
+        }
+
+        knob_drag_pointer(
+            ui,
+            &response,
+            self.hide_pointer,
+            self.lock_pointer && self.drag_mode.is_linear(),
+        );
+
+        let keys = KeyboardSteps::read(ui, &response);
+
+        if !keys.is_empty() {
+            // Arrow keys step by the active snap or by one degree, page keys
+            // by 15 degrees. Home and End only move to explicit limits.
+            let step = if keys.shift {
+                self.shift_snap
+            } else {
+                self.snap
+            }
+            .unwrap_or(TAU / 360.0);
+
+            let prev_value = get(&mut self.get_set_value);
+
+            let new_value = match (keys.home, keys.end) {
+                (true, _) => self.min.unwrap_or(prev_value),
+                (_, true) => self.max.unwrap_or(prev_value),
+                _ => prev_value + keys.offset(step, TAU / 24.0),
+            };
+
+            set(
+                &mut self.get_set_value,
+                wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+            );
+            response.mark_changed();
+        }
+
+        if self.interactive {
+            if let Some(scroll_step) = self.scroll_step {
+                let scroll = ScrollSteps::read(ui, &response);
+
+                if !scroll.is_empty() {
+                    let new_value = scroll.apply(
+                        ui,
+                        response.id.with("scroll"),
+                        get(&mut self.get_set_value),
+                        scroll_step,
+                        self.snap,
+                        self.shift_snap,
+                    );
+
+                    set(
+                        &mut self.get_set_value,
+                        wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+                    );
+                    response.mark_changed();
+                }
+            }
+        }
+
+        if self.interactive {
+            if let Some(default_value) = self.default_value {
+                if response.double_clicked() {
+                    set(&mut self.get_set_value, default_value);
+                    response.mark_changed();
+                }
+            }
+
+            if let Some(new_value) = angle_inline_editor(
+                ui,
+                &response,
+                get(&mut self.get_set_value),
+                self.unit,
+                self.min,
+                self.max,
+            ) {
+                set(
+                    &mut self.get_set_value,
+                    wrap_constrain_angle(new_value, self.wrap, self.min, self.max),
+                );
+                response.mark_changed();
+            }
+        }
+
+        if let Some(readout) = &self.readout {
+            let value = get(&mut self.get_set_value);
+            show_readout(ui, &response, || readout(self.unit.from_radians(value)));
+        }
+
+        angle_slider_widget_info(
+            ui,
+            &response,
+            get(&mut self.get_set_value),
+            self.unit,
+            self.wrap,
+            self.min,
+            self.max,
+            self.snap,
+        );
+
         if ui.is_rect_visible(rect) {
-            let visuals = *ui.style().interact(&response);
-            let radius = self.diameter / 2.0;
+            let visuals = interact_visuals(ui, &response, self.interactive);
+            let radius = diameter / 2.0;
 
             let value = if self.animated {
-                ui.ctx().animate_value_with_time(
+                animate_value(
+                    ui,
                     response.id,
                     get(&mut self.get_set_value),
-                    ui.style().animation_time,
+                    self.animation,
                 )
             } else {
                 get(&mut self.get_set_value)
@@ -265,10 +589,10 @@ impl<'a> Widget for AngleKnob<'a> {
             let angle_to_shape_outline = |angle: f32| {
                 rotation_matrix
                     * Vec2::angled(angle * self.winding.to_float())
-                    * (self.shape.eval(angle * self.winding.to_float()) * radius)
+                    * (shape.eval(angle * self.winding.to_float()) * radius)
             };
 
-            self.shape.paint_shape(
+            shape.paint_shape(
                 ui,
                 rect.center(),
                 radius,
@@ -277,6 +601,41 @@ impl<'a> Widget for AngleKnob<'a> {
                 self.orientation.rot2(),
             );
 
+            if response.has_focus() {
+                shape.paint_shape(
+                    ui,
+                    rect.center(),
+                    radius + FOCUS_RING_MARGIN,
+                    Color32::TRANSPARENT,
+                    ui.style().visuals.selection.stroke,
+                    self.orientation.rot2(),
+                );
+            }
+
+            if let Some(scale) = &self.scale {
+                let orientation_angle = (rotation_matrix * Vec2::RIGHT).angle();
+                let radians_to_angle =
+                    |radians: f32| orientation_angle + radians * self.winding.to_float();
+                let value_to_angle = |value: f32| radians_to_angle(self.unit.to_radians(value));
+
+                // Divisions span the limits when both are set, a full turn
+                // otherwise.
+                let travel = match (self.min, self.max) {
+                    (Some(min), Some(max)) => (radians_to_angle(min), radians_to_angle(max)),
+                    _ => (orientation_angle, orientation_angle + TAU),
+                };
+
+                scale.paint(
+                    ui,
+                    rect.center(),
+                    radius,
+                    shape,
+                    orientation_angle,
+                    value_to_angle,
+                    travel,
+                );
+            }
+
             {
                 let paint_axis = |axis_angle| {
                     ui.painter().add(Shape::dashed_line(
@@ -338,14 +697,14 @@ impl<'a> Widget for AngleKnob<'a> {
 
                 ui.painter().circle(
                     rect.center(),
-                    self.diameter / 24.0,
+                    diameter / 24.0,
                     visuals.text_color(), // TODO: Semantically correct color
                     visuals.fg_stroke,    // TODO: Semantically correct color
                 );
 
                 ui.painter().circle(
                     rect.center() + angle_to_shape_outline(value),
-                    self.diameter / 24.0,
+                    diameter / 24.0,
                     visuals.text_color(), // TODO: Semantically correct color
                     visuals.fg_stroke,    // TODO: Semantically correct color
                 );
@@ -355,3 +714,107 @@ impl<'a> Widget for AngleKnob<'a> {
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        AngleKnobPreset::iter().for_each(assert_serde_round_trip);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+
+    #[test]
+    fn reports_a_slider() {
+        let mut value = 90.0;
+        let node = accesskit_node(|ui| {
+            ui.add(
+                AngleKnob::new(&mut value)
+                    .unit(AngleUnit::Degrees)
+                    .wrap(WrapMode::Unsigned),
+            )
+        });
+
+        assert_eq!(node.role(), Role::Slider);
+        assert_eq!(node.value(), Some("90.0\u{00B0}"));
+        assert_eq!(node.min_numeric_value(), Some(0.0));
+        assert!((node.max_numeric_value().unwrap() - 360.0).abs() < 1e-3);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod keyboard_tests {
+    use egui::{CentralPanel, Context, Event, Key, Modifiers, RawInput};
+
+    use super::*;
+
+    /// Focuses an angle knob at 1.0 radians, presses `key` on it with the
+    /// pointer resting on the knob, and returns the value afterwards and
+    /// whether the inline editor opened.
+    fn press(key: Key) -> (f32, bool) {
+        let ctx = Context::default();
+        let mut value = 1.0;
+
+        let run = |events: Vec<Event>, value: &mut f32| {
+            let input = RawInput {
+                events,
+                ..Default::default()
+            };
+            let mut knob = None;
+            let _ = ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    let response = ui.add(AngleKnob::new(value));
+                    knob = Some((response.id, response.rect.center()));
+                });
+            });
+            knob.unwrap()
+        };
+
+        let (knob_id, center) = run(Vec::new(), &mut value);
+        ctx.memory_mut(|memory| memory.request_focus(knob_id));
+        run(vec![Event::PointerMoved(center)], &mut value);
+
+        let key_event = |pressed| Event::Key {
+            key,
+            physical_key: None,
+            pressed,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        };
+        run(vec![key_event(true), key_event(false)], &mut value);
+
+        let editor_open = ctx.data(|data| {
+            data.get_temp::<String>(knob_id.with("inline_editor"))
+                .is_some()
+        });
+
+        (value, editor_open)
+    }
+
+    #[test]
+    fn space_leaves_the_value_alone() {
+        assert_eq!(press(Key::Space), (1.0, false));
+    }
+
+    #[test]
+    fn enter_only_opens_the_inline_editor() {
+        assert_eq!(press(Key::Enter), (1.0, true));
+    }
+}
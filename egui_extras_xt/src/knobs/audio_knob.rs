@@ -1,10 +1,21 @@
 use std::f32::consts::TAU;
 use std::ops::RangeInclusive;
 
-use egui::{self, Response, Sense, Ui, Widget};
-use emath::{remap_clamp, Vec2};
-
-use crate::common::{Orientation, WidgetShape, Winding};
+use egui::{self, Color32, Response, Sense, Stroke, Ui, Widget, WidgetInfo};
+use emath::{lerp, Vec2};
+
+use crate::common::{
+    accepts_input, accesskit_numeric_range, animate_value, error_placeholder, inline_editor,
+    interact_inside, interact_visuals, parse_with_unit, reset_animation, validate_snap, Animation,
+    KeyboardSteps, Orientation, ScrollSteps, ValidationError, WidgetShape, Winding,
+    FOCUS_RING_MARGIN,
+};
+use crate::knobs::drag_mode::{
+    knob_drag_delta, knob_drag_pointer, knob_drag_speed, pointer_angle_delta,
+};
+use crate::knobs::readout::show_readout;
+use crate::knobs::{KnobDragMode, KnobScale, Taper};
+use crate::theme::XtTheme;
 
 // ----------------------------------------------------------------------------
 
@@ -26,17 +37,33 @@ fn set(get_set_value: &mut GetSetValue<'_>, value: f32) {
 pub struct AudioKnob<'a> {
     get_set_value: GetSetValue<'a>,
     interactive: bool,
-    diameter: f32,
+    diameter: Option<f32>,
     drag_length: f32,
+    drag_mode: KnobDragMode,
+    fine_speed: f32,
+    hide_pointer: bool,
+    lock_pointer: bool,
     winding: Winding,
     orientation: Orientation,
     range: RangeInclusive<f32>,
+    taper: Taper,
+    origin: f32,
+    origin_detent: bool,
+    detents: Vec<f32>,
+    detent_width: f32,
+    detent_feedback: bool,
     spread: f32,
     thickness: f32,
-    shape: WidgetShape,
+    shape: Option<WidgetShape>,
+    scale: Option<KnobScale>,
     animated: bool,
+    animation: Option<Animation>,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
+    readout: Option<Box<dyn 'a + Fn(f32) -> String>>,
+    label: Option<String>,
 }
 
 impl<'a> AudioKnob<'a> {
@@ -53,17 +80,33 @@ impl<'a> AudioKnob<'a> {
         Self {
             get_set_value: Box::new(get_set_value),
             interactive: true,
-            diameter: 32.0,
+            diameter: None,
             drag_length: 1.0,
+            drag_mode: KnobDragMode::Combined,
+            fine_speed: 0.1,
+            hide_pointer: false,
+            lock_pointer: false,
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
             range: 0.0..=1.0,
+            taper: Taper::Linear,
+            origin: 0.0,
+            origin_detent: false,
+            detents: Vec::new(),
+            detent_width: 0.05,
+            detent_feedback: true,
             spread: 1.0,
             thickness: 0.66,
-            shape: WidgetShape::Squircle(4.0),
+            shape: None,
+            scale: None,
             animated: true,
+            animation: None,
             snap: None,
             shift_snap: None,
+            scroll_step: Some(0.05),
+            default_value: None,
+            readout: None,
+            label: None,
         }
     }
 
@@ -73,7 +116,7 @@ impl<'a> AudioKnob<'a> {
     }
 
     pub fn diameter(mut self, diameter: impl Into<f32>) -> Self {
-        self.diameter = diameter.into();
+        self.diameter = Some(diameter.into());
         self
     }
 
@@ -82,6 +125,29 @@ impl<'a> AudioKnob<'a> {
         self
     }
 
+    pub fn drag_mode(mut self, drag_mode: KnobDragMode) -> Self {
+        self.drag_mode = drag_mode;
+        self
+    }
+
+    /// Drag speed multiplier while Ctrl (Cmd on macOS) is held.
+    pub fn fine_speed(mut self, fine_speed: impl Into<f32>) -> Self {
+        self.fine_speed = fine_speed.into();
+        self
+    }
+
+    pub fn hide_pointer(mut self, hide_pointer: bool) -> Self {
+        self.hide_pointer = hide_pointer;
+        self
+    }
+
+    /// Lock the pointer in place while dragging in a linear drag mode, so
+    /// drags aren't cut short by the edges of the screen.
+    pub fn lock_pointer(mut self, lock_pointer: bool) -> Self {
+        self.lock_pointer = lock_pointer;
+        self
+    }
+
     pub fn winding(mut self, winding: Winding) -> Self {
         self.winding = winding;
         self
@@ -97,6 +163,42 @@ impl<'a> AudioKnob<'a> {
         self
     }
 
+    pub fn taper(mut self, taper: Taper) -> Self {
+        self.taper = taper;
+        self
+    }
+
+    /// Value the painted arc starts from, e.g. the centre of the range for
+    /// bipolar controls like pan or balance.
+    pub fn origin(mut self, origin: impl Into<f32>) -> Self {
+        self.origin = origin.into();
+        self
+    }
+
+    /// Adds a detent at the origin.
+    pub fn origin_detent(mut self, origin_detent: bool) -> Self {
+        self.origin_detent = origin_detent;
+        self
+    }
+
+    /// Values that hold the knob in place while dragging across them.
+    pub fn detents(mut self, detents: impl IntoIterator<Item = f32>) -> Self {
+        self.detents = detents.into_iter().collect();
+        self
+    }
+
+    /// Width of the zone each detent captures, as a fraction of the arc.
+    pub fn detent_width(mut self, detent_width: impl Into<f32>) -> Self {
+        self.detent_width = detent_width.into();
+        self
+    }
+
+    /// Flash a marker on the arc while the knob is held by a detent.
+    pub fn detent_feedback(mut self, detent_feedback: bool) -> Self {
+        self.detent_feedback = detent_feedback;
+        self
+    }
+
     pub fn spread(mut self, spread: impl Into<f32>) -> Self {
         self.spread = spread.into();
         self
@@ -108,7 +210,46 @@ impl<'a> AudioKnob<'a> {
     }
 
     pub fn shape(mut self, shape: WidgetShape) -> Self {
-        self.shape = shape;
+        self.shape = Some(shape);
+        self
+    }
+
+    pub fn try_shape(self, shape: WidgetShape) -> Result<Self, ValidationError> {
+        shape.validate()?;
+        Ok(self.shape(shape))
+    }
+
+    pub fn scale(mut self, scale: KnobScale) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Fraction of the arc to turn by per mouse wheel notch, `None` ignores
+    /// the wheel.
+    /// Ctrl (Cmd on macOS) scrolls in finer steps and Shift in coarser ones,
+    /// snaps take precedence.
+    pub fn scroll_step(mut self, scroll_step: Option<f32>) -> Self {
+        self.scroll_step = scroll_step;
+        self
+    }
+
+    /// Value to reset to on double-click.
+    pub fn default_value(mut self, default_value: Option<f32>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
+    /// Shows the value, formatted with `formatter`, next to the pointer
+    /// while hovering or dragging. See `knobs::readout` for common units.
+    pub fn readout(mut self, formatter: impl 'a + Fn(f32) -> String) -> Self {
+        self.readout = Some(Box::new(formatter));
+        self
+    }
+
+    /// Name of the knob for screen readers, which also read out the value
+    /// through the `readout()` formatter when there is one.
+    pub fn label(mut self, label: impl ToString) -> Self {
+        self.label = Some(label.to_string());
         self
     }
 
@@ -117,6 +258,15 @@ impl<'a> AudioKnob<'a> {
         self
     }
 
+    /// How the displayed value follows changes when `animated`. Defaults to
+    /// linear easing over the `animation_time` of the style.
+    pub fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// Snaps values to multiples of `snap`, in the units of the range. With
+    /// non-linear tapers the snap points aren't evenly spaced along the arc.
     pub fn snap(mut self, snap: Option<f32>) -> Self {
         self.snap = snap;
         self
@@ -126,83 +276,324 @@ impl<'a> AudioKnob<'a> {
         self.shift_snap = shift_snap;
         self
     }
+
+    /// Like `snap()`, but returns an error for a non-positive snap value.
+    pub fn try_snap(self, snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(snap)?;
+        Ok(self.snap(snap))
+    }
+
+    /// Like `shift_snap()`, but returns an error for a non-positive snap value.
+    pub fn try_shift_snap(self, shift_snap: Option<f32>) -> Result<Self, ValidationError> {
+        validate_snap(shift_snap)?;
+        Ok(self.shift_snap(shift_snap))
+    }
+
+    /// Checks the configuration. An invalid configuration is shown as an
+    /// error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_snap(self.snap)?;
+        validate_snap(self.shift_snap)?;
+        self.taper.validate(&self.range)?;
+        self.animation
+            .as_ref()
+            .map_or(Ok(()), Animation::validate)?;
+        self.shape.as_ref().map_or(Ok(()), WidgetShape::validate)
+    }
 }
 
 impl<'a> Widget for AudioKnob<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
-        let desired_size = Vec2::splat(self.diameter);
+        let theme = XtTheme::current(ui);
+        let diameter = self.diameter.unwrap_or(theme.knob_diameter);
+
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        self.interactive = accepts_input(ui, self.interactive);
+
+        let shape = self.shape.as_ref().unwrap_or(&theme.audio_knob_shape);
+
+        if let Err(error) = shape.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        let scale_margin = self.scale.as_ref().map_or(0.0, |scale| scale.margin(ui));
+        let desired_size = Vec2::splat(diameter + 2.0 * scale_margin);
 
-        let (rect, mut response) = ui.allocate_exact_size(
+        let (rect, response) = ui.allocate_exact_size(
             desired_size,
             if self.interactive {
-                Sense::click_and_drag()
+                Sense::focusable_noninteractive()
             } else {
                 Sense::hover()
             },
         );
 
-        let constrain_value = |value: f32| value.clamp(*self.range.start(), *self.range.end());
+        let center_angle = (self.orientation.rot2() * Vec2::RIGHT).angle();
+        let spread_angle = (TAU / 2.0) * self.spread.clamp(0.0, 1.0);
+
+        let (min_angle, max_angle) = (
+            center_angle - spread_angle * self.winding.to_float(),
+            center_angle + spread_angle * self.winding.to_float(),
+        );
+
+        let outer_radius = diameter / 2.0;
+        let inner_radius = outer_radius * (1.0 - self.thickness.clamp(0.0, 1.0));
+
+        let mut response = if self.interactive {
+            interact_inside(response, Sense::click_and_drag(), |pointer_pos| {
+                shape.contains_arc(
+                    rect.center(),
+                    inner_radius,
+                    outer_radius,
+                    min_angle,
+                    max_angle,
+                    self.orientation.rot2(),
+                    pointer_pos,
+                )
+            })
+        } else {
+            response
+        };
+
+        if response.clicked() || response.drag_started() {
+            response.request_focus();
+        }
+
+        // Dragging and painting happen along the arc, in the `0.0..=1.0`
+        // position space of the taper. Snapping and stepping by snaps happen
+        // in value space, so that snaps are equal value steps on any taper.
+        let to_position = |value: f32| self.taper.position(value, &self.range);
+        let to_value = |position: f32| self.taper.value(position, &self.range);
+
+        let (range_min, range_max) = (
+            self.range.start().min(*self.range.end()),
+            self.range.start().max(*self.range.end()),
+        );
+        let snap_value =
+            |value: f32, snap: f32| ((value / snap).round() * snap).clamp(range_min, range_max);
+
+        let detents = self
+            .detents
+            .iter()
+            .copied()
+            .chain(self.origin_detent.then_some(self.origin))
+            .collect::<Vec<_>>();
+
+        let keys = KeyboardSteps::read(ui, &response);
+
+        if !keys.is_empty() {
+            // Arrow keys step by the active snap and page keys by ten snaps,
+            // or by a hundredth and a tenth of the arc without one.
+            let snap = if keys.shift {
+                self.shift_snap
+            } else {
+                self.snap
+            };
+
+            let value = get(&mut self.get_set_value);
+
+            let new_value = if keys.home {
+                to_value(0.0)
+            } else if keys.end {
+                to_value(1.0)
+            } else if let Some(snap) = snap {
+                snap_value(value + keys.offset(snap, snap * 10.0), snap)
+            } else {
+                to_value(to_position(value) + keys.offset(0.01, 0.1))
+            };
+
+            set(&mut self.get_set_value, new_value);
+            response.mark_changed();
+        }
+
+        if self.interactive {
+            if let Some(scroll_step) = self.scroll_step {
+                let scroll = ScrollSteps::read(ui, &response);
+
+                if !scroll.is_empty() {
+                    let value = get(&mut self.get_set_value);
+                    let scroll_id = response.id.with("scroll");
+
+                    // Snapped scrolling lands on snaps in value space, free
+                    // scrolling moves by `scroll_step` along the arc.
+                    let new_value = if scroll.snaps(self.snap, self.shift_snap) {
+                        scroll
+                            .apply(
+                                ui,
+                                scroll_id,
+                                value,
+                                scroll_step,
+                                self.snap,
+                                self.shift_snap,
+                            )
+                            .clamp(range_min, range_max)
+                    } else {
+                        to_value(scroll.apply(
+                            ui,
+                            scroll_id,
+                            to_position(value),
+                            scroll_step,
+                            None,
+                            None,
+                        ))
+                    };
+
+                    set(&mut self.get_set_value, new_value);
+                    response.mark_changed();
+                }
+            }
+        }
 
         if response.dragged() {
-            let drag_delta = self.orientation.rot2().inverse() * response.drag_delta();
+            // Detents hold the value, so the unheld drag position has to be
+            // tracked separately for the knob to be able to leave them.
+            let drag_position_id = response.id.with("drag_position");
+
+            let drag_position = if response.drag_started() {
+                to_position(get(&mut self.get_set_value))
+            } else {
+                ui.data(|data| data.get_temp(drag_position_id))
+                    .unwrap_or_else(|| to_position(get(&mut self.get_set_value)))
+            };
+
+            let locked = self.lock_pointer && self.drag_mode.is_linear();
+            let drag_delta = knob_drag_delta(ui, &response, locked);
+            let speed = knob_drag_speed(ui, self.fine_speed);
+            let arc_span = (2.0 * spread_angle).max(f32::EPSILON);
+
+            let new_position = match self.drag_mode {
+                KnobDragMode::Rotary => {
+                    let pointer_angle =
+                        (response.interact_pointer_pos().unwrap() - rect.center()).angle();
+                    let arc_angle =
+                        ((pointer_angle - min_angle) * self.winding.to_float()).rem_euclid(TAU);
+
+                    // Pointers in the gap between the ends of the arc go to
+                    // the nearer end.
+                    if arc_angle <= arc_span {
+                        arc_angle / arc_span
+                    } else if arc_angle - arc_span < TAU - arc_angle {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                KnobDragMode::RelativeRotary => {
+                    let angle_delta = pointer_angle_delta(&response, rect.center());
+                    drag_position + speed * angle_delta * self.winding.to_float() / arc_span
+                }
+                _ => {
+                    let delta =
+                        self.drag_mode
+                            .linear_delta(drag_delta, self.orientation, self.winding);
+                    drag_position + speed * delta / (diameter * self.drag_length)
+                }
+            }
+            .clamp(0.0, 1.0);
 
-            let mut new_value = get(&mut self.get_set_value);
+            ui.data_mut(|data| data.insert_temp(drag_position_id, new_position));
 
-            let delta = drag_delta.x + drag_delta.y * self.winding.to_float();
-            new_value += delta * (self.range.end() - self.range.start())
-                / (self.diameter * self.drag_length);
+            let detent = detents.iter().copied().find(|&detent| {
+                (to_position(detent) - new_position).abs() <= self.detent_width / 2.0
+            });
 
-            set(&mut self.get_set_value, constrain_value(new_value));
+            set(
+                &mut self.get_set_value,
+                detent.unwrap_or_else(|| to_value(new_position)),
+            );
             response.mark_changed();
         }
 
+        knob_drag_pointer(
+            ui,
+            &response,
+            self.hide_pointer,
+            self.lock_pointer && self.drag_mode.is_linear(),
+        );
+
         if response.drag_stopped() {
             if self.animated {
-                ui.ctx().clear_animations();
-                ui.ctx().animate_value_with_time(
-                    response.id,
-                    get(&mut self.get_set_value),
-                    ui.style().animation_time,
-                );
+                reset_animation(ui, response.id, to_position(get(&mut self.get_set_value)));
             }
 
-            if let Some(snap_angle) = if ui.input(|input| input.modifiers.shift_only()) {
+            let held = detents.contains(&get(&mut self.get_set_value));
+
+            if let Some(snap) = if ui.input(|input| input.modifiers.shift_only()) {
                 self.shift_snap
             } else {
                 self.snap
-            } {
-                assert!(
-                    snap_angle > 0.0,
-                    "non-positive snap angles are not supported"
-                );
-                let new_value = (get(&mut self.get_set_value) / snap_angle).round() * snap_angle;
-                set(&mut self.get_set_value, constrain_value(new_value));
+            }
+            .filter(|_| !held)
+            {
+                let new_value = snap_value(get(&mut self.get_set_value), snap);
+                set(&mut self.get_set_value, new_value);
                 response.mark_changed();
             }
         }
 
-        if ui.is_rect_visible(rect) {
-            let visuals = *ui.style().interact(&response);
+        if self.interactive {
+            if let Some(default_value) = self.default_value {
+                if response.double_clicked() {
+                    set(&mut self.get_set_value, default_value);
+                    response.mark_changed();
+                }
+            }
 
-            let value = if self.animated && !response.dragged() {
-                ui.ctx()
-                    .animate_value_with_time(response.id, get(&mut self.get_set_value), 0.1)
-            } else {
-                get(&mut self.get_set_value)
-            };
+            // Values are typed in the units of the range, not as positions
+            // along the arc.
+            if let Some(new_value) = inline_editor(
+                ui,
+                &response,
+                get(&mut self.get_set_value),
+                |value| format!("{value:.3}"),
+                |text| {
+                    parse_with_unit(text, "")
+                        .filter(|value| (range_min..=range_max).contains(value))
+                },
+            ) {
+                set(&mut self.get_set_value, new_value);
+                response.mark_changed();
+            }
+        }
 
-            let center_angle = (self.orientation.rot2() * Vec2::RIGHT).angle();
-            let spread_angle = (TAU / 2.0) * self.spread.clamp(0.0, 1.0);
+        if let Some(readout) = &self.readout {
+            let value = get(&mut self.get_set_value);
+            show_readout(ui, &response, || readout(value));
+        }
 
-            let (min_angle, max_angle) = (
-                center_angle - spread_angle * self.winding.to_float(),
-                center_angle + spread_angle * self.winding.to_float(),
-            );
+        let enabled = ui.is_enabled();
+        let value = get(&mut self.get_set_value);
+        response.widget_info(|| WidgetInfo {
+            current_text_value: Some(match &self.readout {
+                Some(readout) => readout(value),
+                None => format!("{value:.3}"),
+            }),
+            ..WidgetInfo::slider(
+                enabled,
+                f64::from(value),
+                self.label.as_deref().unwrap_or(""),
+            )
+        });
+        accesskit_numeric_range(&response, Some(self.range.clone()), self.snap);
+
+        if ui.is_rect_visible(rect) {
+            let visuals = interact_visuals(ui, &response, self.interactive);
 
-            let outer_radius = self.diameter / 2.0;
-            let inner_radius = outer_radius * (1.0 - self.thickness.clamp(0.0, 1.0));
+            let position = if self.animated && !response.dragged() {
+                animate_value(
+                    ui,
+                    response.id,
+                    to_position(get(&mut self.get_set_value)),
+                    self.animation,
+                )
+            } else {
+                to_position(get(&mut self.get_set_value))
+            };
 
-            self.shape.paint_arc(
+            shape.paint_arc(
                 ui,
                 rect.center(),
                 inner_radius,
@@ -214,19 +605,113 @@ impl<'a> Widget for AudioKnob<'a> {
                 self.orientation.rot2(),
             );
 
-            self.shape.paint_arc(
+            shape.paint_arc(
                 ui,
                 rect.center(),
                 (inner_radius - visuals.expansion).max(0.0),
                 outer_radius + visuals.expansion,
-                remap_clamp(0.0, self.range.clone(), min_angle..=max_angle),
-                remap_clamp(value, self.range, min_angle..=max_angle),
+                lerp(min_angle..=max_angle, to_position(self.origin)),
+                lerp(min_angle..=max_angle, position),
                 visuals.bg_fill,
                 visuals.fg_stroke,
                 self.orientation.rot2(),
             );
+
+            if let Some(scale) = &self.scale {
+                scale.paint(
+                    ui,
+                    rect.center(),
+                    outer_radius,
+                    shape,
+                    center_angle,
+                    |value| lerp(min_angle..=max_angle, to_position(value)),
+                    (min_angle, max_angle),
+                );
+            }
+
+            if self.detent_feedback && !detents.is_empty() {
+                let value = get(&mut self.get_set_value);
+                let held = response.dragged() && detents.contains(&value);
+
+                let feedback = ui.ctx().animate_bool_with_time(
+                    response.id.with("detent_feedback"),
+                    held,
+                    ui.style().animation_time,
+                );
+
+                if feedback > 0.0 {
+                    let angle = lerp(min_angle..=max_angle, to_position(value));
+                    let direction = Vec2::angled(angle);
+                    let shape_radius = shape.eval(angle - center_angle);
+
+                    ui.painter().line_segment(
+                        [
+                            rect.center() + direction * inner_radius * shape_radius,
+                            rect.center()
+                                + direction
+                                    * (outer_radius * shape_radius
+                                        + visuals.expansion
+                                        + FOCUS_RING_MARGIN),
+                        ],
+                        Stroke::new(
+                            visuals.fg_stroke.width * 2.0,
+                            ui.style()
+                                .visuals
+                                .selection
+                                .stroke
+                                .color
+                                .gamma_multiply(feedback),
+                        ),
+                    );
+                }
+            }
+
+            if response.has_focus() {
+                shape.paint_arc(
+                    ui,
+                    rect.center(),
+                    (inner_radius - FOCUS_RING_MARGIN).max(0.0),
+                    outer_radius + FOCUS_RING_MARGIN,
+                    min_angle,
+                    max_angle,
+                    Color32::TRANSPARENT,
+                    ui.style().visuals.selection.stroke,
+                    self.orientation.rot2(),
+                );
+            }
         }
 
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "accesskit"))]
+mod accesskit_tests {
+    use egui::accesskit::Role;
+
+    use super::*;
+    use crate::common::accesskit_node;
+    use crate::knobs::readout;
+
+    #[test]
+    fn reports_a_labelled_slider() {
+        let mut value = 0.5;
+        let node = accesskit_node(|ui| {
+            ui.add(
+                AudioKnob::new(&mut value)
+                    .range(0.0..=2.0)
+                    .label("Gain")
+                    .readout(readout::gain_decibels),
+            )
+        });
+
+        assert_eq!(node.role(), Role::Slider);
+        assert_eq!(node.label(), Some("Gain"));
+        assert_eq!(node.numeric_value(), Some(0.5));
+        assert_eq!(node.min_numeric_value(), Some(0.0));
+        assert_eq!(node.max_numeric_value(), Some(2.0));
+        assert_eq!(node.value(), Some("-6.0 dB"));
+    }
+}
@@ -0,0 +1,127 @@
+use egui::{CursorGrab, CursorIcon, Pos2, Response, Ui, Vec2, ViewportCommand};
+use emath::normalized_angle;
+use strum::{Display, EnumIter};
+
+use crate::common::{Orientation, Winding};
+
+// ----------------------------------------------------------------------------
+
+/// How pointer drags are turned into knob movement.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Display, EnumIter, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum KnobDragMode {
+    /// Dragging up increases the value.
+    #[strum(to_string = "Vertical")]
+    Vertical,
+
+    /// Dragging right increases the value.
+    #[strum(to_string = "Horizontal")]
+    Horizontal,
+
+    /// Both axes, relative to the knob's orientation and winding.
+    #[strum(to_string = "Combined")]
+    Combined,
+
+    /// The knob jumps to the angle of the pointer around its centre.
+    #[strum(to_string = "Rotary")]
+    Rotary,
+
+    /// The knob turns by as much as the pointer does around its centre,
+    /// without jumping to it.
+    #[strum(to_string = "Relative rotary")]
+    RelativeRotary,
+}
+
+impl KnobDragMode {
+    #[must_use]
+    pub fn is_linear(&self) -> bool {
+        matches!(
+            self,
+            KnobDragMode::Vertical | KnobDragMode::Horizontal | KnobDragMode::Combined
+        )
+    }
+
+    /// Drag distance in points along the axes of a linear mode.
+    pub(crate) fn linear_delta(
+        &self,
+        drag_delta: Vec2,
+        orientation: Orientation,
+        winding: Winding,
+    ) -> f32 {
+        match self {
+            KnobDragMode::Vertical => -drag_delta.y,
+            KnobDragMode::Horizontal => drag_delta.x,
+            KnobDragMode::Combined => {
+                let drag_delta = orientation.rot2().inverse() * drag_delta;
+                drag_delta.x + drag_delta.y * winding.to_float()
+            }
+            KnobDragMode::Rotary | KnobDragMode::RelativeRotary => 0.0,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Change in the pointer's screen angle around `center` during this frame.
+pub(crate) fn pointer_angle_delta(response: &Response, center: Pos2) -> f32 {
+    response.interact_pointer_pos().map_or(0.0, |pointer_pos| {
+        let prev_pointer_pos = pointer_pos - response.drag_delta();
+        normalized_angle((pointer_pos - center).angle() - (prev_pointer_pos - center).angle())
+    })
+}
+
+/// Drag delta of this frame. Locked pointers don't move, their raw motion is
+/// used instead.
+pub(crate) fn knob_drag_delta(ui: &Ui, response: &Response, locked: bool) -> Vec2 {
+    if locked {
+        ui.input(|input| input.pointer.motion())
+            .unwrap_or(response.drag_delta())
+    } else {
+        response.drag_delta()
+    }
+}
+
+/// `fine_speed` while the fine adjustment modifier (Ctrl, or Cmd on macOS)
+/// is held, `1.0` otherwise.
+pub(crate) fn knob_drag_speed(ui: &Ui, fine_speed: f32) -> f32 {
+    if ui.input(|input| input.modifiers.command_only()) {
+        fine_speed
+    } else {
+        1.0
+    }
+}
+
+/// Hides and locks the pointer for the duration of a drag.
+pub(crate) fn knob_drag_pointer(ui: &Ui, response: &Response, hide: bool, lock: bool) {
+    if hide && response.dragged() {
+        ui.ctx().set_cursor_icon(CursorIcon::None);
+    }
+
+    if lock {
+        if response.drag_started() {
+            ui.ctx()
+                .send_viewport_cmd(ViewportCommand::CursorGrab(CursorGrab::Locked));
+        }
+
+        if response.drag_stopped() {
+            ui.ctx()
+                .send_viewport_cmd(ViewportCommand::CursorGrab(CursorGrab::None));
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        KnobDragMode::iter().for_each(assert_serde_round_trip);
+    }
+}
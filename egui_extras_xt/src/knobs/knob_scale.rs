@@ -0,0 +1,308 @@
+use std::f32::consts::TAU;
+
+use egui::{Align2, FontId, Ui};
+use emath::{lerp, Pos2, Vec2};
+use epaint::{Color32, Stroke};
+
+use crate::common::{RotatedText, WidgetShape};
+
+// ----------------------------------------------------------------------------
+
+/// A single tick mark of a `KnobScale`, placed at a knob value.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ScaleTick {
+    pub value: f32,
+    pub major: bool,
+    pub label: Option<String>,
+    pub color: Option<Color32>,
+}
+
+impl ScaleTick {
+    pub fn major(value: f32) -> Self {
+        Self {
+            value,
+            major: true,
+            label: None,
+            color: None,
+        }
+    }
+
+    pub fn minor(value: f32) -> Self {
+        Self {
+            major: false,
+            ..Self::major(value)
+        }
+    }
+
+    pub fn label(mut self, label: impl ToString) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Tick marks and labels painted around the outside of a knob.
+///
+/// ```
+/// # use egui::Color32;
+/// # use egui_extras_xt::knobs::{AudioKnob, KnobScale, ScaleTick};
+/// # let mut gain = 1.0;
+/// let scale = KnobScale::new()
+///     .tick(ScaleTick::major(0.0).label("-inf"))
+///     .tick(ScaleTick::major(0.25).label("-12"))
+///     .tick(ScaleTick::major(1.0).label("+6").color(Color32::RED))
+///     .divisions(4, 2);
+///
+/// let knob = AudioKnob::new(&mut gain).range(0.0..=2.0).scale(scale);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct KnobScale {
+    ticks: Vec<ScaleTick>,
+    divisions: Option<(usize, usize)>,
+    major_length: f32,
+    minor_length: f32,
+    spacing: f32,
+    label_height: f32,
+    tangential_labels: bool,
+    color: Option<Color32>,
+}
+
+impl Default for KnobScale {
+    fn default() -> Self {
+        Self {
+            ticks: Vec::new(),
+            divisions: None,
+            major_length: 6.0,
+            minor_length: 3.0,
+            spacing: 2.0,
+            label_height: 10.0,
+            tangential_labels: false,
+            color: None,
+        }
+    }
+}
+
+impl KnobScale {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tick(mut self, tick: ScaleTick) -> Self {
+        self.ticks.push(tick);
+        self
+    }
+
+    pub fn ticks(mut self, ticks: impl IntoIterator<Item = ScaleTick>) -> Self {
+        self.ticks.extend(ticks);
+        self
+    }
+
+    /// Evenly spaced, unlabeled ticks: `major` intervals along the knob's
+    /// travel, each split into `minor` smaller intervals.
+    pub fn divisions(mut self, major: usize, minor: usize) -> Self {
+        self.divisions = Some((major, minor));
+        self
+    }
+
+    pub fn major_length(mut self, major_length: impl Into<f32>) -> Self {
+        self.major_length = major_length.into();
+        self
+    }
+
+    pub fn minor_length(mut self, minor_length: impl Into<f32>) -> Self {
+        self.minor_length = minor_length.into();
+        self
+    }
+
+    /// Gap between the knob outline, the ticks and the labels.
+    pub fn spacing(mut self, spacing: impl Into<f32>) -> Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    pub fn label_height(mut self, label_height: impl Into<f32>) -> Self {
+        self.label_height = label_height.into();
+        self
+    }
+
+    /// Run labels along the outline instead of keeping them upright. Labels
+    /// on the lower half are turned around so that they don't read upside down.
+    pub fn tangential_labels(mut self, tangential_labels: bool) -> Self {
+        self.tangential_labels = tangential_labels;
+        self
+    }
+
+    /// Color of ticks and labels without a color of their own.
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    fn label_size(&self, ui: &Ui, label: &str) -> Vec2 {
+        ui.painter()
+            .layout_no_wrap(
+                label.to_owned(),
+                FontId::proportional(self.label_height),
+                Color32::PLACEHOLDER,
+            )
+            .size()
+    }
+
+    /// Distance between `label_size` centered at `direction` and the point
+    /// it's placed next to.
+    fn label_extent(&self, label_size: Vec2, direction: Vec2) -> f32 {
+        if self.tangential_labels {
+            label_size.y / 2.0
+        } else {
+            (direction.x.abs() * label_size.x + direction.y.abs() * label_size.y) / 2.0
+        }
+    }
+
+    /// Room needed around the knob outline.
+    pub(crate) fn margin(&self, ui: &Ui) -> f32 {
+        let tick_margin = self.spacing + self.major_length.max(self.minor_length);
+
+        let label_margin = self
+            .ticks
+            .iter()
+            .filter_map(|tick| tick.label.as_deref())
+            .map(|label| {
+                let label_size = self.label_size(ui, label);
+                self.spacing + self.label_extent(label_size, Vec2::splat(1.0))
+            })
+            .fold(0.0, f32::max);
+
+        tick_margin + label_margin
+    }
+
+    /// Paints the scale around `shape`. `value_to_angle` maps knob values to
+    /// screen angles, `travel` is the range of screen angles the divisions
+    /// are spread across.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn paint(
+        &self,
+        ui: &Ui,
+        center: Pos2,
+        radius: f32,
+        shape: &WidgetShape,
+        shape_rotation: f32,
+        value_to_angle: impl Fn(f32) -> f32,
+        travel: (f32, f32),
+    ) {
+        let default_color = self
+            .color
+            .unwrap_or(ui.style().visuals.noninteractive().fg_stroke.color);
+        let stroke_width = ui.style().visuals.noninteractive().fg_stroke.width;
+
+        let tick_start = |angle: f32| {
+            let direction = Vec2::angled(angle);
+            (
+                direction,
+                radius * shape.eval(angle - shape_rotation) + self.spacing,
+            )
+        };
+
+        let paint_tick = |angle: f32, major: bool, color: Color32| {
+            let (direction, start) = tick_start(angle);
+            let (length, width) = if major {
+                (self.major_length, stroke_width)
+            } else {
+                (self.minor_length, stroke_width / 2.0)
+            };
+
+            ui.painter().line_segment(
+                [
+                    center + direction * start,
+                    center + direction * (start + length),
+                ],
+                Stroke::new(width, color),
+            );
+        };
+
+        if let Some((major, minor)) = self.divisions {
+            let (start_angle, end_angle) = travel;
+            let full_turn = ((end_angle - start_angle).abs() - TAU).abs() < 1e-3;
+
+            let intervals = major.max(1) * minor.max(1);
+            // Skip the last tick of a full turn, it'd cover the first one
+            let count = if full_turn { intervals } else { intervals + 1 };
+
+            for index in 0..count {
+                let angle = lerp(start_angle..=end_angle, index as f32 / intervals as f32);
+                paint_tick(angle, index % minor.max(1) == 0, default_color);
+            }
+        }
+
+        for tick in &self.ticks {
+            let angle = value_to_angle(tick.value);
+            let color = tick.color.unwrap_or(default_color);
+
+            paint_tick(angle, tick.major, color);
+
+            if let Some(label) = &tick.label {
+                let (direction, start) = tick_start(angle);
+                let label_size = self.label_size(ui, label);
+                let distance = start
+                    + self.major_length.max(self.minor_length)
+                    + self.spacing
+                    + self.label_extent(label_size, direction);
+
+                let rotation = if self.tangential_labels {
+                    // Below the center the baseline would point leftwards
+                    if direction.y > 0.0 {
+                        angle - (TAU / 4.0)
+                    } else {
+                        angle + (TAU / 4.0)
+                    }
+                } else {
+                    0.0
+                };
+
+                ui.painter().rotated_text(
+                    center + direction * distance,
+                    Align2::CENTER_CENTER,
+                    label,
+                    FontId::proportional(self.label_height),
+                    color,
+                    rotation,
+                );
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        let ticks = [
+            ScaleTick::major(0.0).label("-inf"),
+            ScaleTick::minor(0.5),
+            ScaleTick::major(1.0).label("+6").color(Color32::RED),
+        ];
+
+        ticks.iter().cloned().for_each(assert_serde_round_trip);
+
+        assert_serde_round_trip(KnobScale::new());
+        assert_serde_round_trip(
+            KnobScale::new()
+                .ticks(ticks)
+                .divisions(4, 2)
+                .tangential_labels(true)
+                .color(Color32::GRAY),
+        );
+    }
+}
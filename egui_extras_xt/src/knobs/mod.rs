@@ -1,7 +1,14 @@
 mod angle_knob;
 mod audio_knob;
+mod drag_mode;
+mod knob_scale;
+pub mod readout;
+mod taper;
 mod thumbstick_widget;
 
 pub use angle_knob::{AngleKnob, AngleKnobPreset};
 pub use audio_knob::AudioKnob;
+pub use drag_mode::KnobDragMode;
+pub use knob_scale::{KnobScale, ScaleTick};
+pub use taper::Taper;
 pub use thumbstick_widget::{ThumbstickDeadZone, ThumbstickSnap, ThumbstickWidget};
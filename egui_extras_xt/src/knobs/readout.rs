@@ -0,0 +1,61 @@
+//! Formatters for the value readouts of knobs, e.g.
+//! `AngleKnob::new(&mut angle).readout(readout::degrees)`.
+
+use egui::{show_tooltip_at_pointer, Response, Ui};
+
+// ----------------------------------------------------------------------------
+
+/// Angles in radians, shown in degrees.
+#[must_use]
+pub fn degrees(value: f32) -> String {
+    format!("{:.1}°", value.to_degrees())
+}
+
+#[must_use]
+pub fn radians(value: f32) -> String {
+    format!("{value:.3} rad")
+}
+
+/// Fractions, shown as percentages.
+#[must_use]
+pub fn percent(value: f32) -> String {
+    format!("{:.0}%", value * 100.0)
+}
+
+/// Frequencies in hertz, switching to kilohertz from 1000 Hz.
+#[must_use]
+pub fn hertz(value: f32) -> String {
+    if value.abs() >= 1000.0 {
+        format!("{:.2} kHz", value / 1000.0)
+    } else {
+        format!("{value:.1} Hz")
+    }
+}
+
+/// Values that already are in decibels.
+#[must_use]
+pub fn decibels(value: f32) -> String {
+    format!("{value:+.1} dB")
+}
+
+/// Linear amplitudes, shown in decibels.
+#[must_use]
+pub fn gain_decibels(value: f32) -> String {
+    if value <= 0.0 {
+        "-inf dB".to_owned()
+    } else {
+        decibels(20.0 * value.log10())
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Shows `text` in a tooltip next to the pointer while the widget is hovered
+/// or dragged.
+pub(crate) fn show_readout(ui: &Ui, response: &Response, text: impl FnOnce() -> String) {
+    if response.hovered() || response.dragged() {
+        show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), response.id.with("readout"), |ui| {
+            ui.label(text());
+        });
+    }
+}
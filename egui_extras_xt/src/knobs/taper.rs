@@ -0,0 +1,257 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use emath::{lerp, remap_clamp};
+use strum::Display;
+
+use crate::common::ValidationError;
+
+// ----------------------------------------------------------------------------
+
+type TaperFn = Arc<dyn Fn(f32) -> f32 + Send + Sync>;
+
+/// Maps knob values onto knob positions (`0.0..=1.0` along the arc) and back.
+#[non_exhaustive]
+#[derive(Clone, Display)]
+pub enum Taper {
+    #[strum(to_string = "Linear")]
+    Linear,
+
+    /// Equal ratios take equal arc lengths, e.g. for frequencies. The range
+    /// must not contain or touch zero.
+    #[strum(to_string = "Logarithmic")]
+    Logarithmic,
+
+    /// Positive curves spend more of the arc on the low end of the range,
+    /// negative ones on the high end. A curve of `0.0` is linear.
+    #[strum(to_string = "Exponential")]
+    Exponential { curve: f32 },
+
+    /// Amplitude values laid out linearly in decibels, from `min_db` below
+    /// the end of the range up to the end of the range. The start of the arc
+    /// jumps to the start of the range (usually silence).
+    #[strum(to_string = "Decibel")]
+    Decibel { min_db: f32 },
+
+    /// User-supplied mapping, `to_value` takes arc positions to values and
+    /// `to_position` has to be its inverse. Use `Taper::custom` to build one.
+    #[strum(to_string = "Custom")]
+    Custom {
+        to_value: TaperFn,
+        to_position: TaperFn,
+    },
+}
+
+impl Taper {
+    pub fn custom(
+        to_value: impl Fn(f32) -> f32 + Send + Sync + 'static,
+        to_position: impl Fn(f32) -> f32 + Send + Sync + 'static,
+    ) -> Self {
+        Self::Custom {
+            to_value: Arc::new(to_value),
+            to_position: Arc::new(to_position),
+        }
+    }
+
+    /// Arc position of `value`, clamped to `0.0..=1.0`.
+    #[must_use]
+    pub fn position(&self, value: f32, range: &RangeInclusive<f32>) -> f32 {
+        let (start, end) = (*range.start(), *range.end());
+
+        let position = match self {
+            Taper::Linear => remap_clamp(value, start..=end, 0.0..=1.0),
+            Taper::Logarithmic => (value / start).ln() / (end / start).ln(),
+            Taper::Exponential { curve } => {
+                let t = remap_clamp(value, start..=end, 0.0..=1.0);
+                if curve.abs() < f32::EPSILON {
+                    t
+                } else {
+                    (t * curve.exp_m1()).ln_1p() / curve
+                }
+            }
+            Taper::Decibel { min_db } => {
+                if value <= start {
+                    0.0
+                } else {
+                    1.0 - (20.0 * (value / end).log10()) / min_db
+                }
+            }
+            Taper::Custom { to_position, .. } => to_position(value),
+        };
+
+        if position.is_nan() {
+            0.0
+        } else {
+            position.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Value at arc `position`, clamped to `range`.
+    #[must_use]
+    pub fn value(&self, position: f32, range: &RangeInclusive<f32>) -> f32 {
+        let (start, end) = (*range.start(), *range.end());
+        let position = position.clamp(0.0, 1.0);
+
+        let value = match self {
+            Taper::Linear => lerp(start..=end, position),
+            Taper::Logarithmic => start * (end / start).powf(position),
+            Taper::Exponential { curve } => {
+                let t = if curve.abs() < f32::EPSILON {
+                    position
+                } else {
+                    (position * curve).exp_m1() / curve.exp_m1()
+                };
+                lerp(start..=end, t)
+            }
+            Taper::Decibel { min_db } => {
+                if position <= 0.0 {
+                    start
+                } else {
+                    end * 10.0f32.powf(min_db * (1.0 - position) / 20.0)
+                }
+            }
+            Taper::Custom { to_value, .. } => to_value(position),
+        };
+
+        if value.is_nan() {
+            start
+        } else {
+            value.clamp(start.min(end), start.max(end))
+        }
+    }
+
+    /// Checks that this taper can map `range`, invalid combinations map
+    /// everything to the start of the range.
+    pub fn validate(&self, range: &RangeInclusive<f32>) -> Result<(), ValidationError> {
+        let (start, end) = (*range.start(), *range.end());
+
+        match self {
+            Taper::Logarithmic if start * end <= 0.0 => {
+                Err(ValidationError::LogarithmicTaperRange { start, end })
+            }
+            Taper::Decibel { min_db } if *min_db >= 0.0 || end <= 0.0 => {
+                Err(ValidationError::DecibelTaper {
+                    min_db: *min_db,
+                    end,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for Taper {
+    /// Custom tapers are compared by identity.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Taper::Linear, Taper::Linear) | (Taper::Logarithmic, Taper::Logarithmic) => true,
+            (Taper::Exponential { curve: a }, Taper::Exponential { curve: b }) => a == b,
+            (Taper::Decibel { min_db: a }, Taper::Decibel { min_db: b }) => a == b,
+            (
+                Taper::Custom {
+                    to_value: to_value_a,
+                    to_position: to_position_a,
+                },
+                Taper::Custom {
+                    to_value: to_value_b,
+                    to_position: to_position_b,
+                },
+            ) => Arc::ptr_eq(to_value_a, to_value_b) && Arc::ptr_eq(to_position_a, to_position_b),
+            _ => false,
+        }
+    }
+}
+
+impl Debug for Taper {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Taper::Linear => f.write_str("Linear"),
+            Taper::Logarithmic => f.write_str("Logarithmic"),
+            Taper::Exponential { curve } => {
+                f.debug_struct("Exponential").field("curve", curve).finish()
+            }
+            Taper::Decibel { min_db } => f.debug_struct("Decibel").field("min_db", min_db).finish(),
+            Taper::Custom { .. } => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn tapers() -> Vec<(Taper, RangeInclusive<f32>)> {
+        vec![
+            (Taper::Linear, -10.0..=10.0),
+            (Taper::Logarithmic, 20.0..=20000.0),
+            (Taper::Exponential { curve: 3.0 }, 0.0..=100.0),
+            (Taper::Exponential { curve: -3.0 }, 0.0..=100.0),
+            (Taper::Exponential { curve: 0.0 }, 0.0..=100.0),
+            (Taper::Decibel { min_db: -60.0 }, 0.0..=2.0),
+            (
+                Taper::custom(|position| position * position, f32::sqrt),
+                0.0..=1.0,
+            ),
+        ]
+    }
+
+    #[test]
+    fn maps_the_ends_of_the_range() {
+        for (taper, range) in tapers() {
+            let (start, end) = (*range.start(), *range.end());
+
+            assert_eq!(taper.value(0.0, &range), start, "{taper:?}");
+            assert!((taper.value(1.0, &range) - end).abs() < 1e-3, "{taper:?}");
+            assert_eq!(taper.position(start, &range), 0.0, "{taper:?}");
+            assert!(
+                (taper.position(end, &range) - 1.0).abs() < 1e-6,
+                "{taper:?}"
+            );
+
+            // Anything outside is clamped
+            assert_eq!(taper.value(-1.0, &range), start, "{taper:?}");
+            assert_eq!(
+                taper.value(2.0, &range),
+                taper.value(1.0, &range),
+                "{taper:?}"
+            );
+            assert_eq!(taper.position(start - 1.0, &range), 0.0, "{taper:?}");
+            assert_eq!(taper.position(end * 2.0, &range), 1.0, "{taper:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use proptest::prelude::*;
+
+    use super::tests::tapers;
+
+    proptest! {
+        #[test]
+        fn positions_round_trip(position in 0.0_f32..=1.0) {
+            for (taper, range) in tapers() {
+                let round_trip = taper.position(taper.value(position, &range), &range);
+                prop_assert!((round_trip - position).abs() < 1e-3, "{taper:?}: {position} -> {round_trip}");
+            }
+        }
+
+        #[test]
+        fn values_grow_along_the_arc(a in 0.0_f32..=1.0, b in 0.0_f32..=1.0) {
+            let (low, high) = (a.min(b), a.max(b));
+
+            for (taper, range) in tapers() {
+                prop_assert!(taper.value(low, &range) <= taper.value(high, &range), "{taper:?}");
+
+                let (low_value, high_value) = (taper.value(low, &range), taper.value(high, &range));
+                prop_assert!(
+                    taper.position(low_value, &range) <= taper.position(high_value, &range),
+                    "{taper:?}"
+                );
+            }
+        }
+    }
+}
@@ -1,11 +1,17 @@
 use std::f32::consts::TAU;
 use std::ops::RangeInclusive;
 
-use egui::{self, lerp, remap_clamp, Response, Sense, Ui, Widget};
+use egui::{self, lerp, remap_clamp, Color32, Response, Sense, Ui, Widget};
 use emath::{vec2, Rot2, Vec2};
 use strum::Display;
 
-use crate::common::paint_ellipse;
+use crate::common::{
+    accepts_input, animate_value, error_placeholder, interact_inside, interact_visuals,
+    paint_ellipse, validate_normalized, Animation, KeyboardSteps, ValidationError, WidgetShape,
+    FOCUS_RING_MARGIN,
+};
+use crate::knobs::readout::{degrees, show_readout};
+use crate::theme::XtTheme;
 
 // ----------------------------------------------------------------------------
 
@@ -25,6 +31,7 @@ fn set(get_set_value: &mut GetSetValue<'_>, value: (f32, f32)) {
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ThumbstickSnap {
     #[strum(to_string = "None")]
     None,
@@ -38,6 +45,20 @@ pub enum ThumbstickSnap {
 }
 
 impl ThumbstickSnap {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match *self {
+            ThumbstickSnap::None => Ok(()),
+            ThumbstickSnap::Strict {
+                axes, threshold, ..
+            } => {
+                if axes == 0 {
+                    return Err(ValidationError::ThumbstickSnapAxes(axes));
+                }
+                validate_normalized("snap threshold", threshold)
+            }
+        }
+    }
+
     fn eval(&self, input: Vec2) -> Vec2 {
         match *self {
             ThumbstickSnap::None => input,
@@ -46,12 +67,6 @@ impl ThumbstickSnap {
                 rotation,
                 threshold,
             } => {
-                assert!(axes > 0, "snapping to non-positive number of axes");
-                assert!(
-                    (0.0..=1.0).contains(&threshold),
-                    "threshold must be normalized"
-                );
-
                 if input.length() >= threshold {
                     let mut angle = input.angle() - rotation;
                     angle = (angle / TAU * (axes as f32)).round() * TAU / (axes as f32);
@@ -70,6 +85,7 @@ impl ThumbstickSnap {
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ThumbstickDeadZone {
     #[strum(to_string = "None")]
     None,
@@ -79,15 +95,19 @@ pub enum ThumbstickDeadZone {
 }
 
 impl ThumbstickDeadZone {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match *self {
+            ThumbstickDeadZone::None => Ok(()),
+            ThumbstickDeadZone::ScaledRadial { dead_zone } => {
+                validate_normalized("dead zone", dead_zone)
+            }
+        }
+    }
+
     fn eval(&self, input: Vec2) -> Vec2 {
         match *self {
             ThumbstickDeadZone::None => input,
             ThumbstickDeadZone::ScaledRadial { dead_zone } => {
-                assert!(
-                    (0.0..=1.0).contains(&dead_zone),
-                    "dead zone must be normalized"
-                );
-
                 let scaled_length = (input.length() - dead_zone).max(0.0) / (1.0 - dead_zone);
                 input.normalized() * scaled_length
             }
@@ -104,12 +124,15 @@ pub struct ThumbstickWidget<'a> {
     range_y: RangeInclusive<f32>,
     precision: f32,
     interactive: bool,
-    diameter: f32,
+    diameter: Option<f32>,
     animated: bool,
+    animation: Option<Animation>,
     auto_center: bool,
     show_axes: bool,
     snap: ThumbstickSnap,
     dead_zone: ThumbstickDeadZone,
+    shape: WidgetShape,
+    readout: Option<Box<dyn 'a + Fn(f32) -> String>>,
 }
 
 impl<'a> ThumbstickWidget<'a> {
@@ -129,12 +152,15 @@ impl<'a> ThumbstickWidget<'a> {
             range_y: -1.0..=1.0,
             precision: 1.0,
             interactive: true,
-            diameter: 96.0,
+            diameter: None,
             animated: true,
+            animation: None,
             auto_center: true,
             show_axes: true,
             snap: ThumbstickSnap::None,
             dead_zone: ThumbstickDeadZone::None,
+            shape: WidgetShape::Circle,
+            readout: None,
         }
     }
 
@@ -144,7 +170,7 @@ impl<'a> ThumbstickWidget<'a> {
     }
 
     pub fn diameter(mut self, diameter: impl Into<f32>) -> Self {
-        self.diameter = diameter.into();
+        self.diameter = Some(diameter.into());
         self
     }
 
@@ -153,6 +179,13 @@ impl<'a> ThumbstickWidget<'a> {
         self
     }
 
+    /// How the displayed value follows changes when `animated`. Defaults to
+    /// linear easing over the `animation_time` of the style.
+    pub fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
     pub fn range(mut self, range: RangeInclusive<f32>) -> Self {
         self.range_x = range.clone();
         self.range_y = range;
@@ -189,31 +222,95 @@ impl<'a> ThumbstickWidget<'a> {
         self
     }
 
+    pub fn try_snap(self, snap: ThumbstickSnap) -> Result<Self, ValidationError> {
+        snap.validate()?;
+        Ok(self.snap(snap))
+    }
+
     pub fn dead_zone(mut self, dead_zone: ThumbstickDeadZone) -> Self {
         self.dead_zone = dead_zone;
         self
     }
+
+    pub fn try_dead_zone(self, dead_zone: ThumbstickDeadZone) -> Result<Self, ValidationError> {
+        dead_zone.validate()?;
+        Ok(self.dead_zone(dead_zone))
+    }
+
+    /// Shape of the gate around the stick, also limiting how far the stick
+    /// can be pushed in each direction.
+    pub fn shape(mut self, shape: WidgetShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn try_shape(self, shape: WidgetShape) -> Result<Self, ValidationError> {
+        shape.validate()?;
+        Ok(self.shape(shape))
+    }
+
+    /// Shows both axes and the distance from the centre, formatted with
+    /// `formatter`, and the angle next to the pointer while hovering or
+    /// dragging. See `knobs::readout` for common units.
+    pub fn readout(mut self, formatter: impl 'a + Fn(f32) -> String) -> Self {
+        self.readout = Some(Box::new(formatter));
+        self
+    }
+
+    /// Checks the configuration. An invalid configuration is shown as an
+    /// error in place of the widget.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.snap.validate()?;
+        self.dead_zone.validate()?;
+        self.animation
+            .as_ref()
+            .map_or(Ok(()), Animation::validate)?;
+        self.shape.validate()
+    }
 }
 
 impl<'a> Widget for ThumbstickWidget<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
-        let desired_size = Vec2::splat(self.diameter);
+        if let Err(error) = self.validate() {
+            return error_placeholder(ui, &error);
+        }
+
+        self.interactive = accepts_input(ui, self.interactive);
+
+        let theme = XtTheme::current(ui);
+        let diameter = self.diameter.unwrap_or(theme.thumbstick_diameter);
+
+        let desired_size = Vec2::splat(diameter);
 
-        let (rect, mut response) = ui.allocate_exact_size(
+        let (rect, response) = ui.allocate_exact_size(
             desired_size,
             if self.interactive {
-                Sense::click_and_drag()
+                Sense::focusable_noninteractive()
             } else {
                 Sense::hover()
             },
         );
 
+        let mut response = if self.interactive {
+            interact_inside(response, Sense::click_and_drag(), |pointer_pos| {
+                self.shape
+                    .contains(rect.center(), diameter / 2.0, Rot2::IDENTITY, pointer_pos)
+            })
+        } else {
+            response
+        };
+
+        if response.clicked() || response.drag_started() {
+            response.request_focus();
+        }
+
         if response.dragged() {
             let mut v =
-                (response.interact_pointer_pos().unwrap() - rect.center()) / (self.diameter / 2.0);
+                (response.interact_pointer_pos().unwrap() - rect.center()) / (diameter / 2.0);
 
-            if v.length() > 1.0 {
-                v = v.normalized();
+            let gate_radius = self.shape.eval(v.angle()).min(1.0);
+            if v.length() > gate_radius {
+                v = v.normalized() * gate_radius;
             }
 
             v = self.dead_zone.eval(v);
@@ -235,22 +332,92 @@ impl<'a> Widget for ThumbstickWidget<'a> {
             response.mark_changed();
         }
 
+        let keys = KeyboardSteps::read(ui, &response);
+
+        if !keys.is_empty() {
+            // Arrow keys move the stick by a tenth of the range (a hundredth
+            // with Shift), page keys by half of the vertical range. Home and
+            // End push the stick to the horizontal limits.
+            let (x, y) = get(&mut self.get_set_value);
+            let mut v = vec2(
+                remap_clamp(x, self.range_x.clone(), -1.0..=1.0),
+                remap_clamp(y, self.range_y.clone(), -1.0..=1.0),
+            );
+
+            let step = if keys.shift { 0.02 } else { 0.2 };
+            v.x += keys.horizontal as f32 * step;
+            v.y -= keys.vertical as f32 * step + keys.page as f32;
+
+            if keys.home {
+                v.x = -1.0;
+            } else if keys.end {
+                v.x = 1.0;
+            }
+
+            let gate_radius = self.shape.eval(v.angle()).min(1.0);
+            if v.length() > gate_radius {
+                v = v.normalized() * gate_radius;
+            }
+
+            v.x = remap_clamp(v.x, -1.0..=1.0, self.range_x.clone());
+            v.y = remap_clamp(v.y, -1.0..=1.0, self.range_y.clone());
+
+            set(&mut self.get_set_value, v.into());
+            response.mark_changed();
+        }
+
+        if let Some(readout) = &self.readout {
+            let (x, y) = get(&mut self.get_set_value);
+            let center = vec2(
+                lerp(self.range_x.clone(), 0.5),
+                lerp(self.range_y.clone(), 0.5),
+            );
+
+            // Angles are counted counter-clockwise from the right, as the
+            // y axis points down.
+            let offset = vec2(x, y) - center;
+            let angle = vec2(offset.x, -offset.y).angle();
+
+            show_readout(ui, &response, || {
+                format!(
+                    "x: {}\ny: {}\nr: {}\nθ: {}",
+                    readout(x),
+                    readout(y),
+                    readout(offset.length()),
+                    degrees(angle)
+                )
+            });
+        }
+
         if ui.is_rect_visible(rect) {
-            let visuals = *ui.style().interact(&response);
+            let visuals = interact_visuals(ui, &response, self.interactive);
 
-            ui.painter().circle(
+            self.shape.paint_shape(
+                ui,
                 rect.center(),
-                self.diameter / 2.0,
+                diameter / 2.0,
                 ui.style().visuals.faint_bg_color,
                 ui.style().visuals.window_stroke(),
+                Rot2::IDENTITY,
             );
 
+            if response.has_focus() {
+                self.shape.paint_shape(
+                    ui,
+                    rect.center(),
+                    diameter / 2.0 + FOCUS_RING_MARGIN,
+                    Color32::TRANSPARENT,
+                    ui.style().visuals.selection.stroke,
+                    Rot2::IDENTITY,
+                );
+            }
+
             if self.show_axes {
                 let paint_snap_axis = |angle| {
                     ui.painter().line_segment(
                         [
                             rect.center(),
-                            rect.center() + Vec2::angled(angle) * (self.diameter / 2.0),
+                            rect.center() + Vec2::angled(angle) * (diameter / 2.0),
                         ],
                         ui.style().visuals.window_stroke(),
                     );
@@ -270,13 +437,10 @@ impl<'a> Widget for ThumbstickWidget<'a> {
             {
                 let (r, theta) = {
                     let mut v = if self.animated {
-                        // Where's .animate_vec2_with_time()?
                         let (x, y) = get(&mut self.get_set_value);
                         vec2(
-                            ui.ctx()
-                                .animate_value_with_time(response.id.with("x"), x, 0.1),
-                            ui.ctx()
-                                .animate_value_with_time(response.id.with("y"), y, 0.1),
+                            animate_value(ui, response.id.with("x"), x, self.animation),
+                            animate_value(ui, response.id.with("y"), y, self.animation),
                         )
                     } else {
                         get(&mut self.get_set_value).into()
@@ -294,9 +458,9 @@ impl<'a> Widget for ThumbstickWidget<'a> {
                     let ellipse_center = rect.center()
                         + Vec2::angled(theta)
                             * r
-                            * ((self.diameter - (self.diameter * tilt_factor * size)) / 2.0);
+                            * ((diameter - (diameter * tilt_factor * size)) / 2.0);
 
-                    let ellipse_size = Vec2::splat(self.diameter)
+                    let ellipse_size = Vec2::splat(diameter)
                         * size
                         * Vec2::new(1.0 - (1.0 - tilt_factor) * r, 1.0);
 
@@ -318,3 +482,24 @@ impl<'a> Widget for ThumbstickWidget<'a> {
         response
     }
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        assert_serde_round_trip(ThumbstickSnap::None);
+        assert_serde_round_trip(ThumbstickSnap::Strict {
+            axes: 8,
+            rotation: 0.25,
+            threshold: 0.5,
+        });
+
+        assert_serde_round_trip(ThumbstickDeadZone::None);
+        assert_serde_round_trip(ThumbstickDeadZone::ScaledRadial { dead_zone: 0.2 });
+    }
+}
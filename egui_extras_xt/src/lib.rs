@@ -1,6 +1,8 @@
 mod hash;
 
+pub mod angle;
 pub mod common;
+pub mod theme;
 
 #[cfg(feature = "barcodes")]
 pub mod barcodes;
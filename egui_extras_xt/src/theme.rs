@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use egui::{Context, Id, Theme, Ui};
+
+#[cfg(feature = "barcodes")]
+use ecolor::Color32;
+
+#[cfg(feature = "compasses")]
+use crate::compasses::DefaultCompassMarkerColor;
+
+#[cfg(feature = "displays")]
+use crate::displays::segmented_display::DisplayMetricsPreset;
+#[cfg(feature = "displays")]
+use crate::displays::{DisplayStyle, DisplayStylePreset};
+
+#[cfg(feature = "knobs")]
+use crate::common::WidgetShape;
+
+// ----------------------------------------------------------------------------
+
+/// Defaults the widgets fall back to when a property isn't set on the widget
+/// itself. A separate theme is kept for dark and light `Visuals`, widgets pick
+/// the one matching `Visuals::dark_mode` of their `Ui`.
+///
+/// ```
+/// # use egui::{Context, Theme};
+/// # use egui_extras_xt::theme::XtTheme;
+/// # let ctx = Context::default();
+/// # #[cfg(feature = "displays")] {
+/// use egui_extras_xt::displays::DisplayStylePreset;
+///
+/// let mut theme = XtTheme::get(&ctx, Theme::Light).as_ref().clone();
+/// theme.display_style = DisplayStylePreset::Amber.style();
+/// XtTheme::set(&ctx, Theme::Light, theme);
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct XtTheme {
+    #[cfg(feature = "barcodes")]
+    pub barcode_foreground_color: Color32,
+    #[cfg(feature = "barcodes")]
+    pub barcode_background_color: Color32,
+
+    #[cfg(feature = "compasses")]
+    pub compass_marker_color: DefaultCompassMarkerColor,
+    #[cfg(feature = "compasses")]
+    pub polar_compass_diameter: f32,
+
+    #[cfg(feature = "displays")]
+    pub display_style: DisplayStyle,
+    #[cfg(feature = "displays")]
+    pub display_metrics_preset: DisplayMetricsPreset,
+    #[cfg(feature = "displays")]
+    pub led_diameter: f32,
+
+    #[cfg(feature = "knobs")]
+    pub knob_diameter: f32,
+    #[cfg(feature = "knobs")]
+    pub audio_knob_shape: WidgetShape,
+    #[cfg(feature = "knobs")]
+    pub angle_knob_shape: WidgetShape,
+    #[cfg(feature = "knobs")]
+    pub thumbstick_diameter: f32,
+}
+
+impl XtTheme {
+    #[must_use]
+    pub fn dark() -> Self {
+        Self {
+            #[cfg(feature = "barcodes")]
+            barcode_foreground_color: Color32::BLACK,
+            #[cfg(feature = "barcodes")]
+            barcode_background_color: Color32::WHITE,
+
+            #[cfg(feature = "compasses")]
+            compass_marker_color: DefaultCompassMarkerColor::HsvByAngle {
+                hue_phase: 0.0,
+                saturation: 1.0,
+                value: 1.0,
+            },
+            #[cfg(feature = "compasses")]
+            polar_compass_diameter: 256.0,
+
+            #[cfg(feature = "displays")]
+            display_style: DisplayStylePreset::Default.style(),
+            #[cfg(feature = "displays")]
+            display_metrics_preset: DisplayMetricsPreset::Default,
+            #[cfg(feature = "displays")]
+            led_diameter: 16.0,
+
+            #[cfg(feature = "knobs")]
+            knob_diameter: 32.0,
+            #[cfg(feature = "knobs")]
+            audio_knob_shape: WidgetShape::Squircle(4.0),
+            #[cfg(feature = "knobs")]
+            angle_knob_shape: WidgetShape::Circle,
+            #[cfg(feature = "knobs")]
+            thumbstick_diameter: 96.0,
+        }
+    }
+
+    /// Dark theme with colors that stand out against light panels: a
+    /// calculator LCD instead of a green one, and darker compass markers.
+    #[must_use]
+    pub fn light() -> Self {
+        Self {
+            #[cfg(feature = "compasses")]
+            compass_marker_color: DefaultCompassMarkerColor::HsvByAngle {
+                hue_phase: 0.0,
+                saturation: 1.0,
+                value: 0.75,
+            },
+
+            #[cfg(feature = "displays")]
+            display_style: DisplayStylePreset::Calculator.style(),
+
+            ..Self::dark()
+        }
+    }
+
+    fn id(theme: Theme) -> Id {
+        Id::new("egui_extras_xt_theme").with(theme)
+    }
+
+    /// The theme stored for `theme` in the context, or the built-in one when
+    /// nothing was stored yet. Themes are shared, not copied for every widget.
+    #[must_use]
+    pub fn get(ctx: &Context, theme: Theme) -> Arc<Self> {
+        ctx.data_mut(|data| {
+            data.get_temp_mut_or_insert_with(Self::id(theme), || {
+                Arc::new(match theme {
+                    Theme::Dark => Self::dark(),
+                    Theme::Light => Self::light(),
+                })
+            })
+            .clone()
+        })
+    }
+
+    pub fn set(ctx: &Context, theme: Theme, xt_theme: XtTheme) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(theme), Arc::new(xt_theme)));
+    }
+
+    /// The theme matching the current `Visuals` of `ui`.
+    #[must_use]
+    pub fn current(ui: &Ui) -> Arc<Self> {
+        Self::get(ui.ctx(), Theme::from_dark_mode(ui.visuals().dark_mode))
+    }
+}
+
+impl Default for XtTheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, any(feature = "compasses", feature = "displays")))]
+mod visuals_tests {
+    use egui::{CentralPanel, Visuals};
+
+    use super::*;
+
+    #[test]
+    fn follows_dark_mode() {
+        let ctx = Context::default();
+        assert_ne!(
+            XtTheme::get(&ctx, Theme::Light),
+            XtTheme::get(&ctx, Theme::Dark)
+        );
+
+        for (visuals, expected) in [
+            (Visuals::light(), XtTheme::light()),
+            (Visuals::dark(), XtTheme::dark()),
+        ] {
+            ctx.set_visuals(visuals);
+            let _ = ctx.run(Default::default(), |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    assert_eq!(*XtTheme::current(ui), expected);
+                });
+            });
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::common::assert_serde_round_trip;
+
+    #[test]
+    fn round_trip() {
+        assert_serde_round_trip(XtTheme::dark());
+        assert_serde_round_trip(XtTheme::light());
+    }
+}
@@ -1,11 +1,15 @@
 use std::f32::consts::TAU;
 
 use eframe::egui::{self, DragValue};
+use strum::IntoEnumIterator;
 
-use egui_extras_xt::displays::{BufferLayout, WaveformDisplayWidget};
+use egui_extras_xt::displays::{
+    BufferLayout, ChannelLayout, SignalEdge, VerticalUnit, WaveformDisplayWidget, WaveformPeaks,
+};
 
 const BUFFER_SIZE: usize = 1024;
 const OUTPUT_FREQUENCY: usize = 44100;
+const LONG_BUFFER_SECONDS: usize = 60;
 
 struct WaveformDemoApp {
     enabled: bool,
@@ -13,6 +17,22 @@ struct WaveformDemoApp {
     left_frequency: f32,
     right_frequency: f32,
     phase: f32,
+    noise: f32,
+    free_run: bool,
+    trigger_edge: SignalEdge,
+    trigger_level: f32,
+    trigger_hysteresis: f32,
+    trigger_holdoff: usize,
+    trigger_channel: usize,
+    channel_layout: ChannelLayout,
+    mid_side: bool,
+    correlation_meter: bool,
+    show_graticule: bool,
+    vertical_unit: VerticalUnit,
+    gain: f32,
+    offset: f32,
+    long_buffer: Vec<f32>,
+    long_buffer_peaks: WaveformPeaks,
 }
 
 impl Default for WaveformDemoApp {
@@ -23,13 +43,49 @@ impl Default for WaveformDemoApp {
             left_frequency: 440.0,
             right_frequency: 440.0,
             phase: 0.0,
+            noise: 0.0,
+            free_run: false,
+            trigger_edge: SignalEdge::RisingEdge,
+            trigger_level: 0.0,
+            trigger_hysteresis: 0.0,
+            trigger_holdoff: 0,
+            trigger_channel: 0,
+            channel_layout: ChannelLayout::SideBySide,
+            mid_side: false,
+            correlation_meter: true,
+            show_graticule: true,
+            vertical_unit: VerticalUnit::FullScale,
+            gain: 1.0,
+            offset: 0.0,
+            long_buffer: Vec::new(),
+            long_buffer_peaks: WaveformPeaks::default(),
         };
         tmp.regenerate_buffer();
+        tmp.generate_long_buffer();
         tmp
     }
 }
 
 impl WaveformDemoApp {
+    /// A mono sweep from 20 Hz to 2 kHz with a slow tremolo, for the timeline.
+    fn generate_long_buffer(&mut self) {
+        let length = LONG_BUFFER_SECONDS * OUTPUT_FREQUENCY;
+        let mut phase = 0.0;
+
+        self.long_buffer = (0..length)
+            .map(|index| {
+                let t = index as f32 / length as f32;
+                let frequency = 20.0 * 100.0_f32.powf(t);
+                phase = (phase + frequency / OUTPUT_FREQUENCY as f32) % 1.0;
+
+                let tremolo = 0.6 + 0.4 * (t * LONG_BUFFER_SECONDS as f32 * TAU * 0.25).sin();
+                (phase * TAU).sin() * tremolo
+            })
+            .collect();
+
+        self.long_buffer_peaks = WaveformPeaks::new(&self.long_buffer, 1, BufferLayout::Planar);
+    }
+
     #[allow(clippy::iter_skip_zero)]
     fn regenerate_buffer(&mut self) {
         for (index, sample) in self.buffer.iter_mut().skip(0).step_by(2).enumerate() {
@@ -41,6 +97,15 @@ impl WaveformDemoApp {
             let q = index as f32 * (self.right_frequency / OUTPUT_FREQUENCY as f32) + self.phase;
             *sample = (q * TAU).sin();
         }
+
+        // Cheap xorshift noise, good enough to show off trigger hysteresis
+        let mut state = 0x2545_F491_u32;
+        for sample in &mut self.buffer {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            *sample += (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * self.noise;
+        }
     }
 }
 
@@ -59,6 +124,97 @@ impl eframe::App for WaveformDemoApp {
                 if ui.add(DragValue::new(&mut self.phase).speed(0.1)).changed() {
                     self.regenerate_buffer();
                 }
+
+                if ui
+                    .add(
+                        DragValue::new(&mut self.noise)
+                            .speed(0.01)
+                            .range(0.0..=1.0)
+                            .prefix("Noise: "),
+                    )
+                    .changed()
+                {
+                    self.regenerate_buffer();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.free_run, "Free run");
+
+                ui.add_enabled_ui(!self.free_run, |ui| {
+                    ui.selectable_value(&mut self.trigger_edge, SignalEdge::RisingEdge, "Rising");
+                    ui.selectable_value(&mut self.trigger_edge, SignalEdge::FallingEdge, "Falling");
+                    ui.add(
+                        DragValue::new(&mut self.trigger_level)
+                            .speed(0.01)
+                            .range(-1.0..=1.0)
+                            .prefix("Level: "),
+                    );
+                    ui.add(
+                        DragValue::new(&mut self.trigger_hysteresis)
+                            .speed(0.01)
+                            .range(0.0..=1.0)
+                            .prefix("Hysteresis: "),
+                    );
+                    ui.add(
+                        DragValue::new(&mut self.trigger_holdoff)
+                            .range(0..=BUFFER_SIZE / 2)
+                            .prefix("Holdoff: "),
+                    );
+                    ui.selectable_value(&mut self.trigger_channel, 0, "Trigger on Left");
+                    ui.selectable_value(&mut self.trigger_channel, 1, "Trigger on Right");
+                });
+            });
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label("Channel layout")
+                    .selected_text(self.channel_layout.to_string())
+                    .show_ui(ui, |ui| {
+                        for channel_layout in ChannelLayout::iter() {
+                            ui.selectable_value(
+                                &mut self.channel_layout,
+                                channel_layout,
+                                channel_layout.to_string(),
+                            );
+                        }
+                    });
+
+                ui.add_enabled_ui(self.channel_layout == ChannelLayout::Xy, |ui| {
+                    ui.checkbox(&mut self.mid_side, "Mid/side");
+                    ui.checkbox(&mut self.correlation_meter, "Correlation meter");
+                });
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_graticule, "Graticule");
+
+                ui.selectable_value(
+                    &mut self.vertical_unit,
+                    VerticalUnit::FullScale,
+                    "Full scale",
+                );
+                ui.selectable_value(
+                    &mut self.vertical_unit,
+                    VerticalUnit::Volts { full_scale: 5.0 },
+                    "\u{B1}5 V",
+                );
+                ui.selectable_value(
+                    &mut self.vertical_unit,
+                    VerticalUnit::DecibelsFullScale,
+                    "dBFS",
+                );
+
+                ui.add(
+                    DragValue::new(&mut self.gain)
+                        .speed(0.01)
+                        .range(0.01..=100.0)
+                        .prefix("Gain: "),
+                );
+                ui.add(
+                    DragValue::new(&mut self.offset)
+                        .speed(0.01)
+                        .prefix("Offset: "),
+                );
             });
 
             ui.separator();
@@ -69,7 +225,39 @@ impl eframe::App for WaveformDemoApp {
                     .channels(2)
                     .channel_names(&["Left", "Right"])
                     .buffer(&self.buffer)
-                    .buffer_layout(BufferLayout::Interleaved),
+                    .buffer_layout(BufferLayout::Interleaved)
+                    .free_run(self.free_run)
+                    .trigger_edge(self.trigger_edge)
+                    .trigger_level(self.trigger_level)
+                    .trigger_hysteresis(self.trigger_hysteresis)
+                    .trigger_holdoff(self.trigger_holdoff)
+                    .trigger_channel(self.trigger_channel)
+                    .channel_layout(self.channel_layout)
+                    .mid_side(self.mid_side)
+                    .correlation_meter(self.correlation_meter)
+                    .show_graticule(self.show_graticule)
+                    .sample_rate(OUTPUT_FREQUENCY as f32)
+                    .vertical_unit(self.vertical_unit)
+                    .gain(&mut self.gain)
+                    .offset(&mut self.offset)
+                    .width(512.0)
+                    .height(128.0),
+            );
+
+            ui.separator();
+
+            ui.add(
+                WaveformDisplayWidget::new(&mut self.enabled)
+                    .track_name("Sweep")
+                    .buffer(&self.long_buffer)
+                    .buffer_layout(BufferLayout::Planar)
+                    .peaks(&self.long_buffer_peaks)
+                    .timeline(true)
+                    .show_graticule(self.show_graticule)
+                    .sample_rate(OUTPUT_FREQUENCY as f32)
+                    .vertical_unit(self.vertical_unit)
+                    .width(512.0)
+                    .height(96.0),
             );
 
             ui.separator();
@@ -1,28 +1,39 @@
 use eframe::egui::{DragValue, Grid, Ui};
-use egui_extras_xt::common::{Orientation, WidgetShape, Winding, WrapMode};
-use egui_extras_xt::knobs::{AngleKnob, AngleKnobPreset};
+use egui_extras_xt::angle::{Angle, AngleUnit};
+use egui_extras_xt::common::{Animation, Orientation, WidgetShape, Winding, WrapMode};
+use egui_extras_xt::knobs::{AngleKnob, AngleKnobPreset, KnobDragMode, KnobScale, ScaleTick};
 use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
 use egui_extras_xt::ui::standard_buttons::StandardButtons;
 use egui_extras_xt::ui::widgets_from_iter::{ComboBoxFromIter, SelectableValueFromIter};
 use strum::IntoEnumIterator;
 
-use crate::pages::ui::{widget_orientation_ui, widget_shape_ui};
+use crate::pages::ui::{animation_ui, widget_orientation_ui, widget_shape_ui};
 use crate::pages::PageImpl;
 
 pub struct AngleKnobPage {
     value: f32,
     interactive: bool,
     diameter: f32,
+    drag_mode: KnobDragMode,
+    fine_speed: f32,
+    hide_pointer: bool,
+    lock_pointer: bool,
     preset: AngleKnobPreset,
     orientation: Orientation,
     winding: Winding,
     wrap: WrapMode,
+    unit: AngleUnit,
     shape: WidgetShape,
+    show_scale: bool,
+    show_readout: bool,
     min: Option<f32>,
     max: Option<f32>,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
     animated: bool,
+    animation: Animation,
     show_axes: bool,
     axis_count: usize,
 }
@@ -34,15 +45,25 @@ impl Default for AngleKnobPage {
             preset: AngleKnobPreset::AdobePhotoshop,
             interactive: true,
             diameter: 32.0,
+            drag_mode: KnobDragMode::Rotary,
+            fine_speed: 0.1,
+            hide_pointer: false,
+            lock_pointer: false,
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
             wrap: WrapMode::Unsigned,
+            unit: AngleUnit::Degrees,
             shape: WidgetShape::Circle,
+            show_scale: false,
+            show_readout: true,
             min: None,
             max: None,
             snap: None,
             shift_snap: Some(15.0f32.to_radians()),
+            scroll_step: Some(5.0f32.to_radians()),
+            default_value: None,
             animated: false,
+            animation: Animation::default(),
             show_axes: true,
             axis_count: 4,
         }
@@ -51,22 +72,55 @@ impl Default for AngleKnobPage {
 
 impl PageImpl for AngleKnobPage {
     fn ui(&mut self, ui: &mut Ui) {
-        ui.add(
-            AngleKnob::new(&mut self.value)
-                .interactive(self.interactive)
-                .diameter(self.diameter)
-                .orientation(self.orientation)
-                .winding(self.winding)
-                .shape(self.shape.clone())
-                .wrap(self.wrap)
-                .min(self.min)
-                .max(self.max)
-                .snap(self.snap)
-                .shift_snap(self.shift_snap)
-                .animated(self.animated)
-                .show_axes(self.show_axes)
-                .axis_count(self.axis_count),
-        );
+        // The page keeps its angles in radians, the knob works in `unit`.
+        let unit = self.unit;
+        let to_unit = |radians: Option<f32>| radians.map(|radians| unit.from_radians(radians));
+
+        let scale = KnobScale::new()
+            .divisions(4, 3)
+            .ticks([0.0f32, 90.0, 180.0, 270.0].map(|degrees| {
+                ScaleTick::major(Angle::from_degrees(degrees).to_unit(unit))
+                    .label(format!("{degrees}°"))
+            }));
+
+        let value = &mut self.value;
+        let mut angle_knob = AngleKnob::from_get_set(move |new_value: Option<f32>| {
+            if let Some(new_value) = new_value {
+                *value = unit.to_radians(new_value);
+            }
+            unit.from_radians(*value)
+        })
+        .interactive(self.interactive)
+        .diameter(self.diameter)
+        .drag_mode(self.drag_mode)
+        .fine_speed(self.fine_speed)
+        .hide_pointer(self.hide_pointer)
+        .lock_pointer(self.lock_pointer)
+        .orientation(self.orientation)
+        .winding(self.winding)
+        .shape(self.shape.clone())
+        .wrap(self.wrap)
+        .unit(unit)
+        .min(to_unit(self.min))
+        .max(to_unit(self.max))
+        .snap(to_unit(self.snap))
+        .shift_snap(to_unit(self.shift_snap))
+        .scroll_step(to_unit(self.scroll_step))
+        .default_value(to_unit(self.default_value))
+        .animated(self.animated)
+        .animation(self.animation)
+        .show_axes(self.show_axes)
+        .axis_count(self.axis_count);
+
+        if self.show_scale {
+            angle_knob = angle_knob.scale(scale);
+        }
+
+        if self.show_readout {
+            angle_knob = angle_knob.readout(move |value| unit.format(value));
+        }
+
+        ui.add(angle_knob);
         ui.separator();
 
         Grid::new("angle_knob_properties")
@@ -86,6 +140,24 @@ impl PageImpl for AngleKnobPage {
                 ui.add(DragValue::new(&mut self.diameter));
                 ui.end_row();
 
+                ui.label("Drag mode");
+                ui.push_id("drag_mode_combo", |ui| {
+                    ui.combobox_from_iter("", &mut self.drag_mode, KnobDragMode::iter());
+                });
+                ui.end_row();
+
+                ui.label("Fine speed");
+                ui.add(DragValue::new(&mut self.fine_speed).speed(0.01));
+                ui.end_row();
+
+                ui.label("Hide pointer");
+                ui.checkbox(&mut self.hide_pointer, "");
+                ui.end_row();
+
+                ui.label("Lock pointer");
+                ui.checkbox(&mut self.lock_pointer, "");
+                ui.end_row();
+
                 ui.label("Preset");
                 ui.horizontal(|ui| {
                     if ui
@@ -117,10 +189,20 @@ impl PageImpl for AngleKnobPage {
                 });
                 ui.end_row();
 
+                ui.label("Unit");
+                ui.push_id("unit_combo", |ui| {
+                    ui.combobox_from_iter("", &mut self.unit, AngleUnit::iter());
+                });
+                ui.end_row();
+
                 ui.label("Shape");
                 widget_shape_ui(ui, &mut self.shape);
                 ui.end_row();
 
+                ui.label("Show scale");
+                ui.checkbox(&mut self.show_scale, "");
+                ui.end_row();
+
                 ui.label("Minimum");
                 ui.optional_value_widget(&mut self.min, Ui::drag_angle);
                 ui.end_row();
@@ -137,10 +219,26 @@ impl PageImpl for AngleKnobPage {
                 ui.optional_value_widget(&mut self.shift_snap, Ui::drag_angle);
                 ui.end_row();
 
+                ui.label("Scroll step");
+                ui.optional_value_widget(&mut self.scroll_step, Ui::drag_angle);
+                ui.end_row();
+
+                ui.label("Default value");
+                ui.optional_value_widget(&mut self.default_value, Ui::drag_angle);
+                ui.end_row();
+
+                ui.label("Show readout");
+                ui.checkbox(&mut self.show_readout, "");
+                ui.end_row();
+
                 ui.label("Animated");
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
 
+                ui.label("Animation");
+                animation_ui(ui, &mut self.animation);
+                ui.end_row();
+
                 ui.label("Show axes");
                 ui.checkbox(&mut self.show_axes, "");
                 ui.end_row();
@@ -1,30 +1,44 @@
 use std::ops::RangeInclusive;
 
 use eframe::egui::{DragValue, Grid, Ui};
-use egui_extras_xt::common::{Orientation, WidgetShape, Winding};
-use egui_extras_xt::knobs::AudioKnob;
+use egui_extras_xt::common::{Animation, Orientation, WidgetShape, Winding};
+use egui_extras_xt::knobs::{readout, AudioKnob, KnobDragMode, KnobScale, ScaleTick, Taper};
 use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
 use egui_extras_xt::ui::optional_value_widget::OptionalValueWidget;
-use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
+use egui_extras_xt::ui::widgets_from_iter::{ComboBoxFromIter, SelectableValueFromIter};
 use strum::IntoEnumIterator;
 
-use crate::pages::ui::{widget_orientation_ui, widget_shape_ui};
+use crate::pages::ui::{animation_ui, taper_ui, widget_orientation_ui, widget_shape_ui};
 use crate::pages::PageImpl;
 
 pub struct AudioKnobPage {
     value: f32,
     interactive: bool,
     diameter: f32,
+    drag_mode: KnobDragMode,
+    fine_speed: f32,
+    hide_pointer: bool,
+    lock_pointer: bool,
     drag_length: f32,
     winding: Winding,
     orientation: Orientation,
     range: RangeInclusive<f32>,
+    taper: Taper,
+    origin: f32,
+    origin_detent: bool,
+    detent_width: f32,
+    detent_feedback: bool,
     spread: f32,
     thickness: f32,
     shape: WidgetShape,
+    show_scale: bool,
+    show_readout: bool,
     animated: bool,
+    animation: Animation,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
 }
 
 impl Default for AudioKnobPage {
@@ -33,37 +47,76 @@ impl Default for AudioKnobPage {
             value: 0.0,
             interactive: true,
             diameter: 32.0,
+            drag_mode: KnobDragMode::Combined,
+            fine_speed: 0.1,
+            hide_pointer: false,
+            lock_pointer: false,
             drag_length: 1.0,
             orientation: Orientation::Top,
             winding: Winding::Clockwise,
             range: 0.0..=1.0,
+            taper: Taper::Linear,
+            origin: 0.0,
+            origin_detent: false,
+            detent_width: 0.05,
+            detent_feedback: true,
             spread: 1.0,
             thickness: 0.66,
             shape: WidgetShape::Squircle(4.0),
+            show_scale: false,
+            show_readout: true,
             animated: true,
+            animation: Animation::default(),
             snap: None,
             shift_snap: None,
+            scroll_step: Some(0.05),
+            default_value: None,
         }
     }
 }
 
 impl PageImpl for AudioKnobPage {
     fn ui(&mut self, ui: &mut Ui) {
-        ui.add(
-            AudioKnob::new(&mut self.value)
-                .interactive(self.interactive)
-                .diameter(self.diameter)
-                .drag_length(self.drag_length)
-                .orientation(self.orientation)
-                .winding(self.winding)
-                .range(self.range.clone())
-                .spread(self.spread)
-                .thickness(self.thickness)
-                .shape(self.shape.clone())
-                .animated(self.animated)
-                .snap(self.snap)
-                .shift_snap(self.shift_snap),
+        let scale = KnobScale::new().divisions(4, 2).ticks(
+            [*self.range.start(), self.origin, *self.range.end()]
+                .map(|value| ScaleTick::major(value).label(format!("{value:.1}"))),
         );
+
+        let mut audio_knob = AudioKnob::new(&mut self.value)
+            .interactive(self.interactive)
+            .diameter(self.diameter)
+            .drag_mode(self.drag_mode)
+            .fine_speed(self.fine_speed)
+            .hide_pointer(self.hide_pointer)
+            .lock_pointer(self.lock_pointer)
+            .drag_length(self.drag_length)
+            .orientation(self.orientation)
+            .winding(self.winding)
+            .range(self.range.clone())
+            .taper(self.taper.clone())
+            .origin(self.origin)
+            .origin_detent(self.origin_detent)
+            .detent_width(self.detent_width)
+            .detent_feedback(self.detent_feedback)
+            .spread(self.spread)
+            .thickness(self.thickness)
+            .shape(self.shape.clone())
+            .animated(self.animated)
+            .animation(self.animation)
+            .snap(self.snap)
+            .shift_snap(self.shift_snap)
+            .scroll_step(self.scroll_step)
+            .default_value(self.default_value);
+
+        if self.show_scale {
+            audio_knob = audio_knob.scale(scale);
+        }
+
+        if self.show_readout {
+            audio_knob = audio_knob.readout(readout::percent);
+        }
+
+        ui.add(audio_knob);
         ui.separator();
 
         Grid::new("audio_knob_properties")
@@ -83,6 +136,24 @@ impl PageImpl for AudioKnobPage {
                 ui.add(DragValue::new(&mut self.diameter));
                 ui.end_row();
 
+                ui.label("Drag mode");
+                ui.push_id("drag_mode_combo", |ui| {
+                    ui.combobox_from_iter("", &mut self.drag_mode, KnobDragMode::iter());
+                });
+                ui.end_row();
+
+                ui.label("Fine speed");
+                ui.add(DragValue::new(&mut self.fine_speed).speed(0.01));
+                ui.end_row();
+
+                ui.label("Hide pointer");
+                ui.checkbox(&mut self.hide_pointer, "");
+                ui.end_row();
+
+                ui.label("Lock pointer");
+                ui.checkbox(&mut self.lock_pointer, "");
+                ui.end_row();
+
                 ui.label("Drag length");
                 ui.add(DragValue::new(&mut self.drag_length));
                 ui.end_row();
@@ -101,6 +172,40 @@ impl PageImpl for AudioKnobPage {
                 ui.drag_rangeinclusive(&mut self.range);
                 ui.end_row();
 
+                ui.label("Taper");
+                taper_ui(ui, &mut self.taper);
+                ui.end_row();
+
+                // Logarithmic tapers can't cross zero, fall back to an audio
+                // frequency range.
+                if self.taper == Taper::Logarithmic && self.range.start() * self.range.end() <= 0.0
+                {
+                    self.range = 20.0..=20000.0;
+                    self.value = self.value.clamp(20.0, 20000.0);
+                }
+
+                // Decibel tapers are relative to a positive end of the range.
+                if matches!(self.taper, Taper::Decibel { .. }) && *self.range.end() <= 0.0 {
+                    self.range = 0.0..=1.0;
+                    self.value = self.value.clamp(0.0, 1.0);
+                }
+
+                ui.label("Origin");
+                ui.add(DragValue::new(&mut self.origin));
+                ui.end_row();
+
+                ui.label("Origin detent");
+                ui.checkbox(&mut self.origin_detent, "");
+                ui.end_row();
+
+                ui.label("Detent width");
+                ui.add(DragValue::new(&mut self.detent_width).speed(0.01));
+                ui.end_row();
+
+                ui.label("Detent feedback");
+                ui.checkbox(&mut self.detent_feedback, "");
+                ui.end_row();
+
                 ui.label("Spread");
                 ui.add(DragValue::new(&mut self.spread));
                 ui.end_row();
@@ -113,10 +218,22 @@ impl PageImpl for AudioKnobPage {
                 widget_shape_ui(ui, &mut self.shape);
                 ui.end_row();
 
+                ui.label("Show scale");
+                ui.checkbox(&mut self.show_scale, "");
+                ui.end_row();
+
+                ui.label("Show readout");
+                ui.checkbox(&mut self.show_readout, "");
+                ui.end_row();
+
                 ui.label("Animated");
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
 
+                ui.label("Animation");
+                animation_ui(ui, &mut self.animation);
+                ui.end_row();
+
                 ui.label("Snap");
                 ui.optional_value_widget(&mut self.snap, |ui, value| ui.add(DragValue::new(value)));
                 ui.end_row();
@@ -126,6 +243,18 @@ impl PageImpl for AudioKnobPage {
                     ui.add(DragValue::new(value))
                 });
                 ui.end_row();
+
+                ui.label("Scroll step");
+                ui.optional_value_widget(&mut self.scroll_step, |ui, value| {
+                    ui.add(DragValue::new(value).speed(0.01))
+                });
+                ui.end_row();
+
+                ui.label("Default value");
+                ui.optional_value_widget(&mut self.default_value, |ui, value| {
+                    ui.add(DragValue::new(value))
+                });
+                ui.end_row();
             });
     }
 }
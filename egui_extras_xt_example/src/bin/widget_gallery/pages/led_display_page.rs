@@ -1,10 +1,11 @@
 use std::ops::RangeInclusive;
 
 use eframe::egui::{DragValue, Grid, Ui};
+use egui_extras_xt::common::Animation;
 use egui_extras_xt::displays::{DisplayStyle, DisplayStylePreset, LedDisplay};
 use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
 
-use crate::pages::ui::display_style_ui;
+use crate::pages::ui::{animation_ui, display_style_ui};
 use crate::pages::PageImpl;
 
 pub struct LedDisplayPage {
@@ -15,6 +16,7 @@ pub struct LedDisplayPage {
     style: DisplayStyle,
     style_preset: DisplayStylePreset,
     animated: bool,
+    animation: Animation,
 }
 
 impl Default for LedDisplayPage {
@@ -27,6 +29,7 @@ impl Default for LedDisplayPage {
             style: DisplayStylePreset::Default.style(),
             style_preset: DisplayStylePreset::Default,
             animated: true,
+            animation: Animation::default(),
         }
     }
 }
@@ -39,7 +42,8 @@ impl PageImpl for LedDisplayPage {
                 .padding(self.padding)
                 .range(self.range.clone())
                 .style(self.style)
-                .animated(self.animated),
+                .animated(self.animated)
+                .animation(self.animation),
         );
         ui.separator();
 
@@ -71,6 +75,10 @@ impl PageImpl for LedDisplayPage {
                 ui.label("Animated");
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
+
+                ui.label("Animation");
+                animation_ui(ui, &mut self.animation);
+                ui.end_row();
             });
     }
 }
@@ -1,6 +1,6 @@
 use eframe::egui::{DragValue, Grid, Ui};
 use eframe::epaint::Color32;
-use egui_extras_xt::common::{Winding, WrapMode};
+use egui_extras_xt::common::{Animation, Winding, WrapMode};
 use egui_extras_xt::compasses::{
     CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor, LinearCompass,
 };
@@ -9,7 +9,8 @@ use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
 use strum::IntoEnumIterator;
 
 use crate::pages::ui::{
-    compass_axis_labels_ui, default_compass_marker_color_ui, default_compass_marker_shape_ui,
+    animation_ui, compass_axis_labels_ui, default_compass_marker_color_ui,
+    default_compass_marker_shape_ui,
 };
 use crate::pages::PageImpl;
 
@@ -24,9 +25,12 @@ pub struct LinearCompassPage {
     axis_labels: Vec<String>,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
     min: Option<f32>,
     max: Option<f32>,
     animated: bool,
+    animation: Animation,
     show_cursor: bool,
     show_ticks: bool,
     show_axes: bool,
@@ -52,9 +56,12 @@ impl Default for LinearCompassPage {
             ],
             snap: None,
             shift_snap: Some(10.0f32.to_radians()),
+            scroll_step: Some(5.0f32.to_radians()),
+            default_value: None,
             min: None,
             max: None,
             animated: false,
+            animation: Animation::default(),
             show_cursor: true,
             show_ticks: true,
             show_axes: true,
@@ -81,9 +88,12 @@ impl PageImpl for LinearCompassPage {
                 .snap(self.snap)
                 .axis_labels(CompassAxisLabels::from_slice(&self.axis_labels))
                 .shift_snap(self.shift_snap)
+                .scroll_step(self.scroll_step)
+                .default_value(self.default_value)
                 .min(self.min)
                 .max(self.max)
                 .animated(self.animated)
+                .animation(self.animation)
                 .show_cursor(self.show_cursor)
                 .show_ticks(self.show_ticks)
                 .show_axes(self.show_axes)
@@ -207,6 +217,14 @@ impl PageImpl for LinearCompassPage {
                 ui.optional_value_widget(&mut self.shift_snap, Ui::drag_angle);
                 ui.end_row();
 
+                ui.label("Scroll step");
+                ui.optional_value_widget(&mut self.scroll_step, Ui::drag_angle);
+                ui.end_row();
+
+                ui.label("Default value");
+                ui.optional_value_widget(&mut self.default_value, Ui::drag_angle);
+                ui.end_row();
+
                 ui.label("Minimum");
                 ui.optional_value_widget(&mut self.min, Ui::drag_angle);
                 ui.end_row();
@@ -219,6 +237,10 @@ impl PageImpl for LinearCompassPage {
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
 
+                ui.label("Animation");
+                animation_ui(ui, &mut self.animation);
+                ui.end_row();
+
                 ui.label("Show cursor");
                 ui.checkbox(&mut self.show_cursor, "");
                 ui.end_row();
@@ -1,6 +1,6 @@
 use eframe::egui::{DragValue, Grid, Ui};
 use eframe::epaint::Color32;
-use egui_extras_xt::common::{Orientation, Winding, WrapMode};
+use egui_extras_xt::common::{Animation, Orientation, Winding, WrapMode};
 use egui_extras_xt::compasses::{
     CompassAxisLabels, CompassMarker, CompassMarkerShape, DefaultCompassMarkerColor, PolarCompass,
     PolarCompassOverflow,
@@ -10,8 +10,8 @@ use egui_extras_xt::ui::widgets_from_iter::SelectableValueFromIter;
 use strum::IntoEnumIterator;
 
 use crate::pages::ui::{
-    compass_axis_labels_ui, default_compass_marker_color_ui, default_compass_marker_shape_ui,
-    widget_orientation_ui,
+    animation_ui, compass_axis_labels_ui, default_compass_marker_color_ui,
+    default_compass_marker_shape_ui, widget_orientation_ui,
 };
 use crate::pages::PageImpl;
 
@@ -27,7 +27,10 @@ pub struct PolarCompassPage {
     max: Option<f32>,
     snap: Option<f32>,
     shift_snap: Option<f32>,
+    scroll_step: Option<f32>,
+    default_value: Option<f32>,
     animated: bool,
+    animation: Animation,
     axis_labels: Vec<String>,
     axis_label_height: f32,
     max_distance: f32,
@@ -58,7 +61,10 @@ impl Default for PolarCompassPage {
             max: None,
             snap: None,
             animated: false,
+            animation: Animation::default(),
             shift_snap: Some(15.0f32.to_radians()),
+            scroll_step: Some(5.0f32.to_radians()),
+            default_value: None,
             axis_labels: vec![
                 "N".to_owned(),
                 "E".to_owned(),
@@ -100,8 +106,11 @@ impl PageImpl for PolarCompassPage {
                 .max(self.max)
                 .snap(self.snap)
                 .shift_snap(self.shift_snap)
+                .scroll_step(self.scroll_step)
+                .default_value(self.default_value)
                 .axis_labels(CompassAxisLabels::from_slice(&self.axis_labels))
                 .animated(self.animated)
+                .animation(self.animation)
                 .axis_label_height(self.axis_label_height)
                 .max_distance(self.max_distance)
                 .scale_log_base(self.scale_log_base)
@@ -226,10 +235,22 @@ impl PageImpl for PolarCompassPage {
                 ui.optional_value_widget(&mut self.shift_snap, Ui::drag_angle);
                 ui.end_row();
 
+                ui.label("Scroll step");
+                ui.optional_value_widget(&mut self.scroll_step, Ui::drag_angle);
+                ui.end_row();
+
+                ui.label("Default value");
+                ui.optional_value_widget(&mut self.default_value, Ui::drag_angle);
+                ui.end_row();
+
                 ui.label("Animated");
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
 
+                ui.label("Animation");
+                animation_ui(ui, &mut self.animation);
+                ui.end_row();
+
                 ui.label("Axis labels");
                 compass_axis_labels_ui(ui, &mut self.axis_labels);
                 ui.end_row();
@@ -1,10 +1,13 @@
 use std::ops::RangeInclusive;
 
 use eframe::egui::{DragValue, Grid, Ui};
+use egui_extras_xt::common::{Animation, WidgetShape};
 use egui_extras_xt::knobs::{ThumbstickDeadZone, ThumbstickSnap, ThumbstickWidget};
 use egui_extras_xt::ui::drag_rangeinclusive::DragRangeInclusive;
 
-use crate::pages::ui::{thumbstick_dead_zone_ui, thumbstick_snap_ui};
+use crate::pages::ui::{
+    animation_ui, thumbstick_dead_zone_ui, thumbstick_snap_ui, widget_shape_ui,
+};
 use crate::pages::PageImpl;
 
 pub struct ThumbstickWidgetPage {
@@ -15,10 +18,13 @@ pub struct ThumbstickWidgetPage {
     interactive: bool,
     diameter: f32,
     animated: bool,
+    animation: Animation,
     auto_center: bool,
     show_axes: bool,
+    show_readout: bool,
     snap: ThumbstickSnap,
     dead_zone: ThumbstickDeadZone,
+    shape: WidgetShape,
 }
 
 impl Default for ThumbstickWidgetPage {
@@ -31,29 +37,38 @@ impl Default for ThumbstickWidgetPage {
             interactive: true,
             diameter: 96.0,
             animated: true,
+            animation: Animation::default(),
             auto_center: true,
             show_axes: true,
+            show_readout: true,
             snap: ThumbstickSnap::None,
             dead_zone: ThumbstickDeadZone::None,
+            shape: WidgetShape::Circle,
         }
     }
 }
 
 impl PageImpl for ThumbstickWidgetPage {
     fn ui(&mut self, ui: &mut Ui) {
-        ui.add(
-            ThumbstickWidget::new(&mut self.position)
-                .range_x(self.range_x.clone())
-                .range_y(self.range_y.clone())
-                .precision(self.precision)
-                .interactive(self.interactive)
-                .diameter(self.diameter)
-                .animated(self.animated)
-                .auto_center(self.auto_center)
-                .show_axes(self.show_axes)
-                .snap(self.snap)
-                .dead_zone(self.dead_zone),
-        );
+        let mut thumbstick = ThumbstickWidget::new(&mut self.position)
+            .range_x(self.range_x.clone())
+            .range_y(self.range_y.clone())
+            .precision(self.precision)
+            .interactive(self.interactive)
+            .diameter(self.diameter)
+            .animated(self.animated)
+            .animation(self.animation)
+            .auto_center(self.auto_center)
+            .show_axes(self.show_axes)
+            .snap(self.snap)
+            .dead_zone(self.dead_zone)
+            .shape(self.shape.clone());
+
+        if self.show_readout {
+            thumbstick = thumbstick.readout(|value| format!("{value:.2}"));
+        }
+
+        ui.add(thumbstick);
         ui.separator();
 
         Grid::new("thumbstick_widget_properties")
@@ -93,6 +108,10 @@ impl PageImpl for ThumbstickWidgetPage {
                 ui.checkbox(&mut self.animated, "");
                 ui.end_row();
 
+                ui.label("Animation");
+                animation_ui(ui, &mut self.animation);
+                ui.end_row();
+
                 ui.label("Auto-center");
                 ui.checkbox(&mut self.auto_center, "");
                 ui.end_row();
@@ -101,6 +120,10 @@ impl PageImpl for ThumbstickWidgetPage {
                 ui.checkbox(&mut self.show_axes, "");
                 ui.end_row();
 
+                ui.label("Show readout");
+                ui.checkbox(&mut self.show_readout, "");
+                ui.end_row();
+
                 ui.label("Snap");
                 thumbstick_snap_ui(ui, &mut self.snap);
                 ui.end_row();
@@ -108,6 +131,10 @@ impl PageImpl for ThumbstickWidgetPage {
                 ui.label("Dead zone");
                 thumbstick_dead_zone_ui(ui, &mut self.dead_zone);
                 ui.end_row();
+
+                ui.label("Shape");
+                widget_shape_ui(ui, &mut self.shape);
+                ui.end_row();
             });
     }
 }
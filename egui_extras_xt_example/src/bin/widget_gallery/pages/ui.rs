@@ -4,11 +4,13 @@ use std::str::FromStr;
 use eframe::egui::{DragValue, Grid, Response, TextEdit, Ui};
 use eframe::epaint::Color32;
 
-use egui_extras_xt::common::{Orientation, WidgetShape};
+use egui_extras_xt::common::{
+    Animation, CustomShape, Easing, Orientation, RadiusInterpolation, WidgetShape,
+};
 use egui_extras_xt::compasses::{CompassMarkerShape, DefaultCompassMarkerColor};
 use egui_extras_xt::displays::segmented_display::DisplayMetricsPreset;
 use egui_extras_xt::displays::{DisplayMetrics, DisplayStyle, DisplayStylePreset};
-use egui_extras_xt::knobs::{ThumbstickDeadZone, ThumbstickSnap};
+use egui_extras_xt::knobs::{Taper, ThumbstickDeadZone, ThumbstickSnap};
 use egui_extras_xt::ui::standard_buttons::StandardButtons;
 use egui_extras_xt::ui::widgets_from_iter::ComboBoxFromIter;
 use egui_extras_xt::ui::widgets_from_slice::{ComboBoxFromSlice, SelectableValueFromSlice};
@@ -163,6 +165,10 @@ pub fn widget_shape_ui(ui: &mut Ui, value: &mut WidgetShape) {
                             Box::new(WidgetShape::Circle),
                             Box::new(WidgetShape::Square),
                         ),
+                        WidgetShape::Custom(CustomShape::from_radius_table(
+                            [1.0, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9],
+                            RadiusInterpolation::Linear,
+                        )),
                     ],
                 );
             });
@@ -208,6 +214,27 @@ pub fn widget_shape_ui(ui: &mut Ui, value: &mut WidgetShape) {
                         ui.push_id("shape_b", |ui| widget_shape_ui(ui, shape_b));
                     });
                 }
+                WidgetShape::Custom(shape) => {
+                    if let Some((radii, interpolation)) = shape.radius_table() {
+                        let mut radii = radii.to_vec();
+                        let mut interpolation = interpolation;
+
+                        ui.vertical(|ui| {
+                            ui.push_id("interpolation_combo", |ui| {
+                                ui.combobox_from_iter(
+                                    "",
+                                    &mut interpolation,
+                                    RadiusInterpolation::iter(),
+                                );
+                            });
+                            for radius in &mut radii {
+                                ui.add(DragValue::new(radius).speed(0.01));
+                            }
+                        });
+
+                        *shape = CustomShape::from_radius_table(radii, interpolation);
+                    }
+                }
                 _ => unimplemented!(),
             }
         });
@@ -351,6 +378,34 @@ pub fn compass_axis_labels_ui(ui: &mut Ui, axis_labels: &mut Vec<String>) {
     });
 }
 
+pub fn taper_ui(ui: &mut Ui, value: &mut Taper) {
+    ui.horizontal_centered(|ui| {
+        ui.push_id("taper_combo", |ui| {
+            ui.combobox_from_slice(
+                "",
+                value,
+                &[
+                    Taper::Linear,
+                    Taper::Logarithmic,
+                    Taper::Exponential { curve: 3.0 },
+                    Taper::Decibel { min_db: -60.0 },
+                ],
+            );
+        });
+
+        match value {
+            Taper::Linear | Taper::Logarithmic | Taper::Custom { .. } => {}
+            Taper::Exponential { curve } => {
+                ui.add(DragValue::new(curve).speed(0.1));
+            }
+            Taper::Decibel { min_db } => {
+                ui.add(DragValue::new(min_db).range(-120.0..=-1.0));
+            }
+            _ => unimplemented!(),
+        }
+    });
+}
+
 pub fn thumbstick_snap_ui(ui: &mut Ui, value: &mut ThumbstickSnap) {
     ui.horizontal_centered(|ui| {
         ui.push_id("thumbstick_snap_combo", |ui| {
@@ -407,6 +462,31 @@ pub fn thumbstick_dead_zone_ui(ui: &mut Ui, value: &mut ThumbstickDeadZone) {
     });
 }
 
+pub fn animation_ui(ui: &mut Ui, value: &mut Animation) {
+    ui.horizontal_centered(|ui| {
+        ui.push_id("animation_combo", |ui| {
+            ui.combobox_from_slice("", value, &[Animation::default(), Animation::spring(8.0)]);
+        });
+
+        match value {
+            Animation::Eased { duration, easing } => {
+                ui.add(DragValue::new(duration).speed(0.01).range(0.0..=2.0));
+                ui.push_id("easing_combo", |ui| {
+                    ui.combobox_from_iter("", easing, Easing::iter());
+                });
+            }
+            Animation::Spring {
+                frequency,
+                damping_ratio,
+            } => {
+                ui.add(DragValue::new(frequency).speed(0.1).range(0.0..=50.0));
+                ui.add(DragValue::new(damping_ratio).speed(0.01).range(0.0..=2.0));
+            }
+            _ => unimplemented!(),
+        }
+    });
+}
+
 pub fn pathbuf_ui(ui: &mut Ui, value: &mut PathBuf) -> Response {
     let mut tmp = value.as_os_str().to_string_lossy().to_string();
     let response = ui.text_edit_singleline(&mut tmp);